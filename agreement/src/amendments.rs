@@ -0,0 +1,48 @@
+use common::agreement::types::{Agreement, Amendment, AmendmentStatus, DataKey, Error};
+use soroban_sdk::{Env, Vec};
+use crate::storage::{get_persistent, store_persistent};
+
+pub fn get_amendments(env: &Env, agreement_id: u64) -> Vec<Amendment> {
+    get_persistent(env, &DataKey::Amendments(agreement_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Versions start at 1 and index the list in order
+pub fn get_amendment(env: &Env, agreement_id: u64, version: u32) -> Result<Amendment, Error> {
+    if version == 0 {
+        return Err(Error::AmendmentNotFound);
+    }
+    get_amendments(env, agreement_id)
+        .get(version - 1)
+        .ok_or(Error::AmendmentNotFound)
+}
+
+pub fn store_amendment(env: &Env, agreement_id: u64, amendment: &Amendment) {
+    let mut amendments: Vec<Amendment> = get_amendments(env, agreement_id);
+    if amendment.version > amendments.len() {
+        amendments.push_back(amendment.clone());
+    } else {
+        amendments.set(amendment.version - 1, amendment.clone());
+    }
+    store_persistent(env, &DataKey::Amendments(agreement_id), &amendments);
+}
+
+pub fn has_pending_amendment(env: &Env, agreement_id: u64) -> bool {
+    get_amendments(env, agreement_id).iter().any(|amendment| {
+        amendment.status == AmendmentStatus::Proposed
+            || amendment.status == AmendmentStatus::Accepted
+    })
+}
+
+// A new duration moves the end time by the difference, so time added by a pause is kept
+pub fn apply_amendment(agreement: &mut Agreement, amendment: &Amendment) {
+    if let Some(duration) = amendment.duration {
+        if let (Some(old_duration), Some(end_time)) = (agreement.duration, agreement.end_time) {
+            agreement.end_time = Some((end_time + duration).saturating_sub(old_duration));
+        }
+        agreement.duration = Some(duration);
+    }
+    if let Some(end_time) = amendment.end_time {
+        agreement.end_time = Some(end_time);
+    }
+}
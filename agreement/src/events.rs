@@ -1,4 +1,4 @@
-use soroban_sdk::{Address, Env, IntoVal, Val, Vec};
+use soroban_sdk::{Address, BytesN, Env, IntoVal, Val, Vec};
 use common::agreement::types::AgreementType;
 
 pub enum AgreementEvent {
@@ -8,6 +8,19 @@ pub enum AgreementEvent {
     Fulfilled(u64, u64, Address),
     Completed(u64, Address),
     Terminated(u64, Address),
+    Paused(u64, Address),
+    Resumed(u64, Address, Option<u64>),
+    TermsAttached(u64, u32, BytesN<32>),
+    TermsSigned(u64, Address, u32),
+    AmendmentProposed(u64, u32, Address),
+    AmendmentAccepted(u64, u32, Address),
+    AmendmentRejected(u64, u32, Address),
+    AmendmentApplied(u64, u32),
+    SubscriptionRenewed(u64, u64),
+    SubscriptionLapsed(u64, u64),
+    SubscriptionCancelled(u64, Address),
+    Expired(u64, u64),
+    Reviewed(u64, Address, u32),
 }
 
 impl AgreementEvent {
@@ -19,6 +32,19 @@ impl AgreementEvent {
             AgreementEvent::Fulfilled(..) => stringify!(Fulfilled),
             AgreementEvent::Completed(..) => stringify!(Completed),
             AgreementEvent::Terminated(..) => stringify!(Terminated),
+            AgreementEvent::Paused(..) => stringify!(Paused),
+            AgreementEvent::Resumed(..) => stringify!(Resumed),
+            AgreementEvent::TermsAttached(..) => stringify!(TermsAttached),
+            AgreementEvent::TermsSigned(..) => stringify!(TermsSigned),
+            AgreementEvent::AmendmentProposed(..) => stringify!(AmendmentProposed),
+            AgreementEvent::AmendmentAccepted(..) => stringify!(AmendmentAccepted),
+            AgreementEvent::AmendmentRejected(..) => stringify!(AmendmentRejected),
+            AgreementEvent::AmendmentApplied(..) => stringify!(AmendmentApplied),
+            AgreementEvent::SubscriptionRenewed(..) => stringify!(SubscriptionRenewed),
+            AgreementEvent::SubscriptionLapsed(..) => stringify!(SubscriptionLapsed),
+            AgreementEvent::SubscriptionCancelled(..) => stringify!(SubscriptionCancelled),
+            AgreementEvent::Expired(..) => stringify!(Expired),
+            AgreementEvent::Reviewed(..) => stringify!(Reviewed),
         }
     }
 
@@ -49,6 +75,65 @@ impl AgreementEvent {
                 v.push_back(listing_id.into_val(env));
                 v.push_back(terminator.into_val(env));
             }
+            AgreementEvent::Paused(agreement_id, caller) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(caller.into_val(env));
+            }
+            AgreementEvent::Resumed(agreement_id, caller, end_time) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(caller.into_val(env));
+                v.push_back(end_time.into_val(env));
+            }
+            AgreementEvent::TermsAttached(agreement_id, version, terms_hash) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+                v.push_back(terms_hash.into_val(env));
+            }
+            AgreementEvent::TermsSigned(agreement_id, signer, version) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(signer.into_val(env));
+                v.push_back(version.into_val(env));
+            }
+            AgreementEvent::AmendmentProposed(agreement_id, version, proposer) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+                v.push_back(proposer.into_val(env));
+            }
+            AgreementEvent::AmendmentAccepted(agreement_id, version, accepter) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+                v.push_back(accepter.into_val(env));
+            }
+            AgreementEvent::AmendmentRejected(agreement_id, version, caller) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+                v.push_back(caller.into_val(env));
+            }
+            AgreementEvent::AmendmentApplied(agreement_id, version) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+            }
+            AgreementEvent::SubscriptionRenewed(agreement_id, end_time) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(end_time.into_val(env));
+            }
+            AgreementEvent::SubscriptionLapsed(agreement_id, grace_until) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(grace_until.into_val(env));
+            }
+            AgreementEvent::SubscriptionCancelled(agreement_id, user) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(user.into_val(env));
+            }
+            AgreementEvent::Expired(agreement_id, end_time) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(end_time.into_val(env));
+            }
+            AgreementEvent::Reviewed(agreement_id, reviewer, rating) => {
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(reviewer.into_val(env));
+                v.push_back(rating.into_val(env));
+            }
         }
 
         env.events().publish((self.name(),), v)
@@ -1,24 +1,151 @@
 #![no_std]
 
+mod amendments;
 mod events;
-
+mod migration;
+mod reputation;
+mod schedule;
+mod state_machine;
+mod storage;
+mod subscriptions;
+
+use common::impl_access_control;
+use common::access::{
+    roles::{has_role, init_admin, require_role},
+    types::Role,
+};
 use common::agreement::{
     interface::AgreementContractTrait,
     types::{
-        Agreement, AgreementStatus, AgreementType, DataKey, Error, ADMIN, MARKETPLACE_CONTRACT,
+        Agreement, AgreementPage, AgreementPause, AgreementRole, AgreementStatus,
+        AgreementTerms, AgreementTransition, AgreementType, Amendment, AmendmentStatus, DataKey,
+        Error, Reputation, Review, Subscription, ADMIN, MARKETPLACE_CONTRACT, MAX_PAGE_SIZE,
+        MAX_RATING, MIN_RATING,
     },
 };
+use amendments::{apply_amendment, has_pending_amendment, store_amendment};
 use events::AgreementEvent;
+use migration::migrate_agreements;
+use reputation::{add_rating, get_reputation};
+use state_machine::{get_history, record_transition, role_of, transition};
+use storage::{
+    extend_instance, get_persistent, remove_persistent, store_persistent,
+};
+use schedule::{get_due, store_agreement};
+use subscriptions::store_subscription;
 use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Symbol, Vec};
 
-pub fn require_marketplace_call(env: &Env) {
+pub fn require_marketplace_call(env: &Env) -> Address {
     let marketplace_address: Address = env.storage().instance().get(&MARKETPLACE_CONTRACT).unwrap();
     marketplace_address.require_auth();
+
+    marketplace_address
+}
+
+// Allocate the next agreement ID
+fn next_agreement_id(env: &Env) -> u64 {
+    let agreement_count: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::AgreementCount)
+        .unwrap_or(0);
+    let agreement_id: u64 = agreement_count + 1;
+
+    // Update agreement count
+    env.storage()
+        .instance()
+        .set(&DataKey::AgreementCount, &agreement_id);
+    extend_instance(env);
+
+    agreement_id
+}
+
+// Store a new agreement and add it to the user and listing indexes
+fn store_new_agreement(env: &Env, agreement: &Agreement, marketplace: &Address) {
+    store_agreement(env, agreement);
+    record_transition(
+        env,
+        agreement.id,
+        AgreementStatus::Created,
+        marketplace,
+        AgreementRole::Marketplace,
+    );
+
+    // Add to user's agreements
+    let user_key = DataKey::UserAgreements(agreement.user.clone());
+    let mut user_agreements: Vec<u64> =
+        get_persistent(env, &user_key).unwrap_or_else(|| Vec::new(env));
+    user_agreements.push_back(agreement.id);
+    store_persistent(env, &user_key, &user_agreements);
+
+    // Add to listing's agreements
+    let listing_key = DataKey::ListingAgreements(agreement.listing_id);
+    let mut listing_agreements: Vec<u64> =
+        get_persistent(env, &listing_key).unwrap_or_else(|| Vec::new(env));
+    listing_agreements.push_back(agreement.id);
+    store_persistent(env, &listing_key, &listing_agreements);
+}
+
+// An agreement only starts or, for a purchase, completes once terms are attached and both
+// parties have signed them
+fn require_accepted_terms(env: &Env, agreement_id: u64) -> Result<(), Error> {
+    let terms: AgreementTerms = get_persistent(env, &DataKey::AgreementTerms(agreement_id))
+        .ok_or(Error::TermsNotFound)?;
+    if terms.user_signed_at.is_none() || terms.owner_signed_at.is_none() {
+        return Err(Error::TermsNotAccepted);
+    }
+    Ok(())
+}
+
+// One page of the agreements in `agreement_ids` with the given status and type, starting at
+// `cursor`. A `None` filter matches any agreement.
+fn page_agreements(
+    env: &Env,
+    agreement_ids: Vec<u64>,
+    status: Option<AgreementStatus>,
+    agreement_type: Option<AgreementType>,
+    cursor: u32,
+    limit: u32,
+) -> AgreementPage {
+    // An empty page would hand back the same cursor forever
+    let limit: u32 = limit.clamp(1, MAX_PAGE_SIZE);
+    let mut agreements: Vec<Agreement> = Vec::new(env);
+    let mut index: u32 = cursor;
+
+    while index < agreement_ids.len() && agreements.len() < limit {
+        let agreement_id: u64 = agreement_ids.get(index).unwrap();
+        index += 1;
+
+        let agreement: Agreement = match get_persistent(env, &DataKey::Agreement(agreement_id)) {
+            Some(agreement) => agreement,
+            None => continue,
+        };
+        if status.is_some_and(|status| status != agreement.status) {
+            continue;
+        }
+        if agreement_type.is_some_and(|agreement_type| agreement_type != agreement.agreement_type) {
+            continue;
+        }
+        agreements.push_back(agreement);
+    }
+
+    AgreementPage {
+        agreements,
+        next_cursor: if index < agreement_ids.len() {
+            Some(index)
+        } else {
+            None
+        },
+    }
 }
 
 #[contract]
 pub struct AgreementContract;
 
+impl_access_control!(AgreementContract, |env: &Env| -> Address {
+    env.storage().instance().get(&ADMIN).unwrap()
+});
+
 #[contractimpl]
 impl AgreementContractTrait for AgreementContract {
     fn initialize(env: Env, admin: Address, marketplace_contract_id: Address) -> Result<(), Error> {
@@ -34,24 +161,35 @@ impl AgreementContractTrait for AgreementContract {
         env.storage()
             .instance()
             .set(&DataKey::AgreementCount, &0u64);
+        init_admin(&env, &admin);
         AgreementEvent::Initialized.publish(&env);
         Ok(())
     }
 
     fn version() -> u32 {
-        1
+        2
     }
 
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
         AgreementEvent::Upgraded(Self::version()).publish(&env);
     }
 
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error> {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    // After upgrading from version 1, which kept agreements in instance storage. Moves up to
+    // `limit` agreements per call and returns how many are left, call again until it's 0.
+    fn migrate(env: Env, caller: Address, limit: u32) -> u64 {
+        require_role(&env, Role::Admin, &caller);
+        migrate_agreements(&env, limit)
+    }
+
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, Role::Admin, &caller);
 
         if !env.storage().instance().has::<Symbol>(&state_key) {
             return Err(Error::StateNotAlreadySet);
@@ -73,91 +211,50 @@ impl AgreementContractTrait for AgreementContract {
         is_rental: bool,
         duration: u64,
     ) -> u64 {
-        require_marketplace_call(&env);
+        let marketplace: Address = require_marketplace_call(&env);
 
         let current_time = env.ledger().timestamp();
+        let agreement_id: u64 = next_agreement_id(&env);
 
-        let agreement_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AgreementCount)
-            .unwrap_or(0);
-        let agreement_id: u64 = agreement_count + 1;
-
-        let agreement_type: AgreementType = if is_rental {
-            AgreementType::Lease
-        } else {
-            AgreementType::Purchase
-        };
-
-        let agreement: Agreement = match agreement_type {
-            AgreementType::Purchase => Agreement {
+        let agreement: Agreement = if is_rental {
+            Agreement {
                 id: agreement_id,
-                agreement_type: AgreementType::Purchase,
+                agreement_type: AgreementType::Lease,
                 user: user.clone(),
                 owner: owner.clone(),
                 listing_id,
                 timestamp: env.ledger().timestamp(),
-                shares,
-                duration: None,
-                end_time: None,
+                shares: 0, // Not applicable for lease
+                duration: Some(duration),
+                end_time: Some(duration + current_time),
                 status: AgreementStatus::Created,
-            },
-            AgreementType::Lease => Agreement {
+            }
+        } else {
+            Agreement {
                 id: agreement_id,
-                agreement_type: AgreementType::Lease,
+                agreement_type: AgreementType::Purchase,
                 user: user.clone(),
                 owner: owner.clone(),
                 listing_id,
                 timestamp: env.ledger().timestamp(),
-                shares: 0, // Not applicable for lease
-                duration: Some(duration),
-                end_time: Some(duration + current_time),
+                shares,
+                duration: None,
+                end_time: None,
                 status: AgreementStatus::Created,
-            },
+            }
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Agreement(agreement_id), &agreement);
-
-        // Update agreement count
-        env.storage()
-            .instance()
-            .set(&DataKey::AgreementCount, &agreement_id);
-
-        // Add to user's agreements
-        let mut user_agreements: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserAgreements(user.clone()))
-            .unwrap_or_else(|| Vec::new(&env));
-        user_agreements.push_back(agreement_id);
-        env.storage()
-            .instance()
-            .set(&DataKey::UserAgreements(user.clone()), &user_agreements);
-
-        // Add to listing's agreements
-        let mut listing_agreements: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::ListingAgreements(listing_id))
-            .unwrap_or_else(|| Vec::new(&env));
-        listing_agreements.push_back(agreement_id);
-        env.storage()
-            .instance()
-            .set(&DataKey::ListingAgreements(listing_id), &listing_agreements);
+        store_new_agreement(&env, &agreement, &marketplace);
 
-        AgreementEvent::Created(agreement_id, listing_id, user, agreement_type).publish(&env);
+        AgreementEvent::Created(agreement_id, listing_id, user, agreement.agreement_type)
+            .publish(&env);
 
         agreement_id
     }
 
     fn get_agreement(env: Env, agreement_id: u64) -> Result<Agreement, Error> {
-        let agreement: Option<Agreement> = env
-            .storage()
-            .instance()
-            .get::<_, Agreement>(&DataKey::Agreement(agreement_id));
+        let agreement: Option<Agreement> =
+            get_persistent(&env, &DataKey::Agreement(agreement_id));
         if agreement.is_some() {
             Ok(agreement.unwrap())
         } else {
@@ -165,19 +262,32 @@ impl AgreementContractTrait for AgreementContract {
         }
     }
 
-    fn get_user_agreements(env: Env, user: Address) -> Vec<u64> {
-        env.storage()
-            .instance()
-            .get(&DataKey::UserAgreements(user))
-            .unwrap_or_else(|| Vec::new(&env))
+    fn get_user_agreements(
+        env: Env,
+        user: Address,
+        status: Option<AgreementStatus>,
+        agreement_type: Option<AgreementType>,
+        cursor: u32,
+        limit: u32,
+    ) -> AgreementPage {
+        let agreement_ids: Vec<u64> = get_persistent(&env, &DataKey::UserAgreements(user))
+            .unwrap_or_else(|| Vec::new(&env));
+        page_agreements(&env, agreement_ids, status, agreement_type, cursor, limit)
     }
 
-    // Get all agreements for a listing
-    fn get_listing_agreements(env: Env, listing_id: u64) -> Vec<u64> {
-        env.storage()
-            .instance()
-            .get(&DataKey::ListingAgreements(listing_id))
-            .unwrap_or_else(|| Vec::new(&env))
+    // Get the agreements for a listing, a page at a time
+    fn get_listing_agreements(
+        env: Env,
+        listing_id: u64,
+        status: Option<AgreementStatus>,
+        agreement_type: Option<AgreementType>,
+        cursor: u32,
+        limit: u32,
+    ) -> AgreementPage {
+        let agreement_ids: Vec<u64> =
+            get_persistent(&env, &DataKey::ListingAgreements(listing_id))
+                .unwrap_or_else(|| Vec::new(&env));
+        page_agreements(&env, agreement_ids, status, agreement_type, cursor, limit)
     }
 
     fn get_agreement_status(env: Env, agreement_id: u64) -> Result<AgreementStatus, Error> {
@@ -186,17 +296,23 @@ impl AgreementContractTrait for AgreementContract {
     }
 
     fn owner_fulfilled(env: Env, agreement_id: u64) -> Result<bool, Error> {
-        require_marketplace_call(&env);
+        let marketplace: Address = require_marketplace_call(&env);
         let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
 
         if agreement.status != AgreementStatus::Created {
             return Err(Error::AgreementNotActive);
         }
 
-        agreement.status = AgreementStatus::Active;
-        env.storage()
-            .instance()
-            .set(&DataKey::Agreement(agreement_id), &agreement);
+        // Only activates once both parties have accepted the terms
+        require_accepted_terms(&env, agreement_id)?;
+
+        transition(
+            &env,
+            &mut agreement,
+            AgreementStatus::Active,
+            &marketplace,
+            AgreementRole::Marketplace,
+        )?;
 
         AgreementEvent::Fulfilled(agreement_id, agreement.listing_id, agreement.user).publish(&env);
 
@@ -207,20 +323,25 @@ impl AgreementContractTrait for AgreementContract {
         require_marketplace_call(&env);
         let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
 
-        if agreement.user != user || agreement.owner != user {
-            return Err(Error::AgreementNotOwnedByCaller);
+        if agreement.status == AgreementStatus::Paused {
+            return Err(Error::AgreementPaused);
         }
 
-        if agreement.status != AgreementStatus::Created
-            || agreement.status != AgreementStatus::Active
-        {
-            return Err(Error::AgreementNotActive);
+        // A purchase completes straight from `Created`, so its terms are checked here
+        if agreement.status == AgreementStatus::Created {
+            require_accepted_terms(&env, agreement_id)?;
         }
 
-        agreement.status = AgreementStatus::Completed;
-        env.storage()
-            .instance()
-            .set(&DataKey::Agreement(agreement_id), &agreement);
+        // Completed on behalf of one of the parties, who is recorded as its actor
+        let role: AgreementRole = if agreement.user == user {
+            AgreementRole::User
+        } else if agreement.owner == user {
+            AgreementRole::Owner
+        } else {
+            return Err(Error::AgreementNotOwnedByCaller);
+        };
+
+        transition(&env, &mut agreement, AgreementStatus::Completed, &user, role)?;
 
         AgreementEvent::Completed(agreement_id, user).publish(&env);
 
@@ -239,16 +360,605 @@ impl AgreementContractTrait for AgreementContract {
             return Err(Error::AgreementNotOwnedByCaller);
         }
 
+        if agreement.status == AgreementStatus::Paused {
+            return Err(Error::AgreementPaused);
+        }
+
         if agreement.status == AgreementStatus::Active {
             return Err(Error::AgreementIsAlreadyActive);
         }
 
-        agreement.status = AgreementStatus::Terminated;
-        env.storage()
-            .instance()
-            .set(&DataKey::Agreement(agreement_id), &agreement);
+        transition(
+            &env,
+            &mut agreement,
+            AgreementStatus::Terminated,
+            &terminator,
+            AgreementRole::Owner,
+        )?;
 
         AgreementEvent::Terminated(agreement_id, terminator).publish(&env);
         Ok(true)
     }
+
+    // Either party can pause an active agreement, e.g. while the item is in repair or a
+    // dispute is open. The rental clock stops until the agreement is resumed. Called through
+    // the marketplace, which takes the renter's control of the token away for the pause.
+    fn pause_agreement(env: Env, agreement_id: u64, caller: Address) -> Result<(), Error> {
+        require_marketplace_call(&env);
+        caller.require_auth();
+
+        let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let role: AgreementRole = match role_of(&env, &agreement, &caller) {
+            Some(role @ (AgreementRole::User | AgreementRole::Owner)) => role,
+            _ => return Err(Error::AgreementNotOwnedByCaller),
+        };
+
+        if agreement.status != AgreementStatus::Active {
+            return Err(Error::AgreementNotActive);
+        }
+
+        let pause: AgreementPause = AgreementPause {
+            paused_by: caller.clone(),
+            paused_at: env.ledger().timestamp(),
+        };
+
+        transition(&env, &mut agreement, AgreementStatus::Paused, &caller, role)?;
+        store_persistent(&env, &DataKey::AgreementPause(agreement_id), &pause);
+
+        AgreementEvent::Paused(agreement_id, caller).publish(&env);
+        Ok(())
+    }
+
+    // The party that paused, the owner or an arbitrator can resume, so a user's pause can't
+    // hold the asset indefinitely. A lease's end time is pushed back by the time spent paused;
+    // returns the updated end time, 0 for purchases.
+    fn resume_agreement(env: Env, agreement_id: u64, caller: Address) -> Result<u64, Error> {
+        require_marketplace_call(&env);
+        caller.require_auth();
+
+        let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        if agreement.status != AgreementStatus::Paused {
+            return Err(Error::AgreementNotPaused);
+        }
+
+        let pause: AgreementPause = Self::get_agreement_pause(env.clone(), agreement_id)
+            .ok_or(Error::AgreementNotPaused)?;
+        let role: AgreementRole = if pause.paused_by == caller || agreement.owner == caller {
+            role_of(&env, &agreement, &caller).ok_or(Error::AgreementNotOwnedByCaller)?
+        } else {
+            if !has_role(&env, Role::Arbitrator, &caller) {
+                return Err(Error::AgreementNotOwnedByCaller);
+            }
+            AgreementRole::Arbitrator
+        };
+
+        let paused_for: u64 = env.ledger().timestamp() - pause.paused_at;
+        agreement.end_time = agreement.end_time.map(|end_time| end_time + paused_for);
+
+        transition(&env, &mut agreement, AgreementStatus::Active, &caller, role)?;
+        remove_persistent(&env, &DataKey::AgreementPause(agreement_id));
+
+        AgreementEvent::Resumed(agreement_id, caller, agreement.end_time).publish(&env);
+        Ok(agreement.end_time.unwrap_or(0))
+    }
+
+    fn get_agreement_pause(env: Env, agreement_id: u64) -> Option<AgreementPause> {
+        get_persistent(&env, &DataKey::AgreementPause(agreement_id))
+    }
+
+    fn get_agreement_history(env: Env, agreement_id: u64) -> Vec<AgreementTransition> {
+        get_history(&env, agreement_id)
+    }
+
+    // Either party attaches the terms document while the agreement is still `Created`, so the
+    // user can propose terms as well as the owner. Re-attaching (e.g. a new version) clears
+    // any earlier acceptance, so it's only allowed until the other party has signed.
+    fn attach_terms(
+        env: Env,
+        agreement_id: u64,
+        party: Address,
+        terms_hash: BytesN<32>,
+        version: u32,
+    ) -> Result<(), Error> {
+        party.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        if agreement.owner != party && agreement.user != party {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        if agreement.status != AgreementStatus::Created {
+            return Err(Error::AgreementIsAlreadyActive);
+        }
+
+        if let Some(current) = Self::get_agreement_terms(env.clone(), agreement_id) {
+            let counterparty_signed: bool = if party == agreement.owner {
+                current.user_signed_at.is_some()
+            } else {
+                current.owner_signed_at.is_some()
+            };
+            if counterparty_signed {
+                return Err(Error::TermsAlreadySigned);
+            }
+        }
+
+        let terms: AgreementTerms = AgreementTerms {
+            terms_hash: terms_hash.clone(),
+            version,
+            user_signed_at: None,
+            owner_signed_at: None,
+        };
+        store_persistent(&env, &DataKey::AgreementTerms(agreement_id), &terms);
+
+        AgreementEvent::TermsAttached(agreement_id, version, terms_hash).publish(&env);
+        Ok(())
+    }
+
+    // A party accepts the terms by signing the hash they reviewed, which must match the
+    // attached document. Returns true once both parties have signed.
+    fn sign_terms(
+        env: Env,
+        agreement_id: u64,
+        signer: Address,
+        terms_hash: BytesN<32>,
+    ) -> Result<bool, Error> {
+        signer.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut terms: AgreementTerms = Self::get_agreement_terms(env.clone(), agreement_id)
+            .ok_or(Error::TermsNotFound)?;
+
+        if terms.terms_hash != terms_hash {
+            return Err(Error::TermsMismatch);
+        }
+
+        if agreement.status != AgreementStatus::Created {
+            return Err(Error::AgreementIsAlreadyActive);
+        }
+
+        let now: u64 = env.ledger().timestamp();
+        if signer == agreement.user {
+            terms.user_signed_at = Some(now);
+        } else if signer == agreement.owner {
+            terms.owner_signed_at = Some(now);
+        } else {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        store_persistent(&env, &DataKey::AgreementTerms(agreement_id), &terms);
+
+        AgreementEvent::TermsSigned(agreement_id, signer, terms.version).publish(&env);
+        Ok(terms.user_signed_at.is_some() && terms.owner_signed_at.is_some())
+    }
+
+    fn get_agreement_terms(env: Env, agreement_id: u64) -> Option<AgreementTerms> {
+        get_persistent(&env, &DataKey::AgreementTerms(agreement_id))
+    }
+
+    // Parties that have accepted the current terms
+    fn get_terms_signers(env: Env, agreement_id: u64) -> Vec<Address> {
+        let mut signers: Vec<Address> = Vec::new(&env);
+        let agreement: Option<Agreement> = Self::get_agreement(env.clone(), agreement_id).ok();
+
+        if let (Some(agreement), Some(terms)) =
+            (agreement, Self::get_agreement_terms(env.clone(), agreement_id))
+        {
+            if terms.user_signed_at.is_some() {
+                signers.push_back(agreement.user);
+            }
+            if terms.owner_signed_at.is_some() {
+                signers.push_back(agreement.owner);
+            }
+        }
+
+        signers
+    }
+
+    // Either party proposes new terms for a live agreement, one proposal at a time
+    fn propose_amendment(
+        env: Env,
+        agreement_id: u64,
+        proposer: Address,
+        duration: Option<u64>,
+        end_time: Option<u64>,
+        price_delta: i128,
+    ) -> Result<u32, Error> {
+        proposer.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        if agreement.user != proposer && agreement.owner != proposer {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        if agreement.status != AgreementStatus::Created
+            && agreement.status != AgreementStatus::Active
+        {
+            return Err(Error::AgreementNotActive);
+        }
+
+        // Only a lease's timing can be amended, the shares a purchase covers are moved by the NFT
+        if agreement.agreement_type != AgreementType::Lease
+            || (duration.is_none() && end_time.is_none())
+        {
+            return Err(Error::InvalidAmendment);
+        }
+
+        if has_pending_amendment(&env, agreement_id) {
+            return Err(Error::AmendmentPending);
+        }
+
+        let now: u64 = env.ledger().timestamp();
+        let amendment: Amendment = Amendment {
+            version: amendments::get_amendments(&env, agreement_id).len() + 1,
+            proposer: proposer.clone(),
+            duration,
+            end_time,
+            price_delta,
+            status: AmendmentStatus::Proposed,
+            proposed_at: now,
+            updated_at: now,
+        };
+        store_amendment(&env, agreement_id, &amendment);
+
+        AgreementEvent::AmendmentProposed(agreement_id, amendment.version, proposer).publish(&env);
+        Ok(amendment.version)
+    }
+
+    // The counterparty accepts. The amendment applies once the marketplace settles it, which
+    // moves any price delta and re-syncs the renter's control of the token.
+    fn accept_amendment(
+        env: Env,
+        agreement_id: u64,
+        accepter: Address,
+        version: u32,
+    ) -> Result<AmendmentStatus, Error> {
+        accepter.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut amendment: Amendment = amendments::get_amendment(&env, agreement_id, version)?;
+
+        if (agreement.user != accepter && agreement.owner != accepter)
+            || amendment.proposer == accepter
+        {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        if amendment.status != AmendmentStatus::Proposed {
+            return Err(Error::InvalidAmendment);
+        }
+
+        if agreement.status != AgreementStatus::Created
+            && agreement.status != AgreementStatus::Active
+        {
+            return Err(Error::AgreementNotActive);
+        }
+
+        amendment.status = AmendmentStatus::Accepted;
+        amendment.updated_at = env.ledger().timestamp();
+        store_amendment(&env, agreement_id, &amendment);
+
+        AgreementEvent::AmendmentAccepted(agreement_id, version, accepter).publish(&env);
+
+        Ok(amendment.status)
+    }
+
+    // The counterparty rejects, or the proposer withdraws, a proposal not yet accepted
+    fn reject_amendment(
+        env: Env,
+        agreement_id: u64,
+        caller: Address,
+        version: u32,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut amendment: Amendment = amendments::get_amendment(&env, agreement_id, version)?;
+
+        if agreement.user != caller && agreement.owner != caller {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        if amendment.status != AmendmentStatus::Proposed {
+            return Err(Error::InvalidAmendment);
+        }
+
+        amendment.status = AmendmentStatus::Rejected;
+        amendment.updated_at = env.ledger().timestamp();
+        store_amendment(&env, agreement_id, &amendment);
+
+        AgreementEvent::AmendmentRejected(agreement_id, version, caller).publish(&env);
+        Ok(())
+    }
+
+    // Called by the marketplace once the price delta has moved through escrow
+    fn settle_amendment(env: Env, agreement_id: u64, version: u32) -> Result<Amendment, Error> {
+        require_marketplace_call(&env);
+
+        let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut amendment: Amendment = amendments::get_amendment(&env, agreement_id, version)?;
+
+        if amendment.status != AmendmentStatus::Accepted {
+            return Err(Error::InvalidAmendment);
+        }
+
+        apply_amendment(&mut agreement, &amendment);
+        amendment.status = AmendmentStatus::Applied;
+        amendment.updated_at = env.ledger().timestamp();
+
+        store_agreement(&env, &agreement);
+        store_amendment(&env, agreement_id, &amendment);
+
+        AgreementEvent::AmendmentApplied(agreement_id, version).publish(&env);
+        Ok(amendment)
+    }
+
+    fn get_amendment(env: Env, agreement_id: u64, version: u32) -> Result<Amendment, Error> {
+        amendments::get_amendment(&env, agreement_id, version)
+    }
+
+    fn get_amendments(env: Env, agreement_id: u64) -> Vec<Amendment> {
+        amendments::get_amendments(&env, agreement_id)
+    }
+
+    // The marketplace collects the first period up front, so the subscription starts active
+    fn create_subscription(
+        env: Env,
+        listing_id: u64,
+        user: Address,
+        owner: Address,
+        period: u64,
+        price: i128,
+        grace_period: u64,
+    ) -> u64 {
+        let marketplace: Address = require_marketplace_call(&env);
+
+        let current_time = env.ledger().timestamp();
+        let agreement_id: u64 = next_agreement_id(&env);
+
+        let mut agreement = Agreement {
+            id: agreement_id,
+            agreement_type: AgreementType::Subscription,
+            user: user.clone(),
+            owner,
+            listing_id,
+            timestamp: current_time,
+            shares: 0, // Not applicable for subscription
+            duration: Some(period),
+            end_time: Some(current_time + period),
+            status: AgreementStatus::Created,
+        };
+        store_new_agreement(&env, &agreement, &marketplace);
+
+        store_subscription(
+            &env,
+            agreement_id,
+            &Subscription {
+                period,
+                price,
+                grace_period,
+                renewals: 0,
+                cancelled: false,
+            },
+        );
+
+        transition(
+            &env,
+            &mut agreement,
+            AgreementStatus::Active,
+            &marketplace,
+            AgreementRole::Marketplace,
+        )
+        .unwrap();
+
+        AgreementEvent::Created(agreement_id, listing_id, user, AgreementType::Subscription)
+            .publish(&env);
+
+        agreement_id
+    }
+
+    fn get_subscription(env: Env, agreement_id: u64) -> Result<Subscription, Error> {
+        subscriptions::get_subscription(&env, agreement_id)
+    }
+
+    // Subscriptions past their `end_time` that are waiting on a renewal attempt
+    fn get_due_subscriptions(env: Env, limit: u32) -> Vec<u64> {
+        get_due(&env, AgreementType::Subscription, limit)
+    }
+
+    // Called by the marketplace after it tried to collect the next period. A paid renewal
+    // extends the agreement by one period, an unpaid one moves it into grace, and a
+    // subscription still unpaid once the grace period is over is terminated. Nothing can be
+    // paid after the grace period.
+    fn record_renewal(env: Env, agreement_id: u64, paid: bool) -> Result<AgreementStatus, Error> {
+        let marketplace: Address = require_marketplace_call(&env);
+
+        let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut subscription: Subscription =
+            subscriptions::get_subscription(&env, agreement_id)?;
+
+        let current_time = env.ledger().timestamp();
+        let end_time: u64 = agreement.end_time.ok_or(Error::NotASubscription)?;
+        if current_time < end_time {
+            return Err(Error::SubscriptionNotDue);
+        }
+
+        if subscription.cancelled {
+            let (status, event) = if agreement.status == AgreementStatus::Grace {
+                (
+                    AgreementStatus::Terminated,
+                    AgreementEvent::Terminated(agreement_id, marketplace.clone()),
+                )
+            } else {
+                (
+                    AgreementStatus::Completed,
+                    AgreementEvent::Completed(agreement_id, agreement.user.clone()),
+                )
+            };
+            transition(&env, &mut agreement, status, &marketplace, AgreementRole::Marketplace)?;
+
+            event.publish(&env);
+            return Ok(agreement.status);
+        }
+
+        if paid {
+            if current_time > end_time + subscription.grace_period {
+                return Err(Error::GracePeriodOver);
+            }
+            agreement.end_time = Some(end_time + subscription.period);
+            subscription.renewals += 1;
+            store_subscription(&env, agreement_id, &subscription);
+
+            if agreement.status == AgreementStatus::Grace {
+                transition(
+                    &env,
+                    &mut agreement,
+                    AgreementStatus::Active,
+                    &marketplace,
+                    AgreementRole::Marketplace,
+                )?;
+            } else {
+                store_agreement(&env, &agreement);
+            }
+
+            AgreementEvent::SubscriptionRenewed(agreement_id, end_time + subscription.period)
+                .publish(&env);
+        } else if agreement.status == AgreementStatus::Active {
+            transition(
+                &env,
+                &mut agreement,
+                AgreementStatus::Grace,
+                &marketplace,
+                AgreementRole::Marketplace,
+            )?;
+
+            AgreementEvent::SubscriptionLapsed(agreement_id, end_time + subscription.grace_period)
+                .publish(&env);
+        } else if current_time > end_time + subscription.grace_period {
+            transition(
+                &env,
+                &mut agreement,
+                AgreementStatus::Terminated,
+                &marketplace,
+                AgreementRole::Marketplace,
+            )?;
+
+            AgreementEvent::Terminated(agreement_id, marketplace).publish(&env);
+        }
+
+        Ok(agreement.status)
+    }
+
+    // The subscriber keeps access until the current period ends, then it is not renewed
+    fn cancel_subscription(env: Env, agreement_id: u64, user: Address) -> Result<(), Error> {
+        user.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        let mut subscription: Subscription =
+            subscriptions::get_subscription(&env, agreement_id)?;
+
+        if agreement.user != user {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+
+        subscription.cancelled = true;
+        store_subscription(&env, agreement_id, &subscription);
+
+        AgreementEvent::SubscriptionCancelled(agreement_id, user).publish(&env);
+        Ok(())
+    }
+
+    // Active leases past their end time, for the marketplace's expiry sweep
+    fn get_expired_leases(env: Env, limit: u32) -> Vec<u64> {
+        get_due(&env, AgreementType::Lease, limit)
+    }
+
+    // The rental period is over but the item hasn't been returned yet. The owner completes
+    // the agreement through the marketplace once it's back.
+    fn mark_expired(env: Env, agreement_id: u64) -> Result<(), Error> {
+        let marketplace: Address = require_marketplace_call(&env);
+        let mut agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+
+        let end_time: u64 = agreement.end_time.ok_or(Error::AgreementNotExpired)?;
+        if agreement.agreement_type != AgreementType::Lease
+            || env.ledger().timestamp() < end_time
+        {
+            return Err(Error::AgreementNotExpired);
+        }
+
+        transition(
+            &env,
+            &mut agreement,
+            AgreementStatus::Overdue,
+            &marketplace,
+            AgreementRole::Marketplace,
+        )?;
+
+        AgreementEvent::Expired(agreement_id, end_time).publish(&env);
+        Ok(())
+    }
+
+    // Each party to a completed agreement rates the other once. A user's rating of the owner
+    // also counts towards the listing.
+    fn leave_review(
+        env: Env,
+        agreement_id: u64,
+        reviewer: Address,
+        rating: u32,
+        review_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        reviewer.require_auth();
+
+        let agreement: Agreement = Self::get_agreement(env.clone(), agreement_id)?;
+        if agreement.status != AgreementStatus::Completed {
+            return Err(Error::AgreementNotCompleted);
+        }
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            return Err(Error::InvalidRating);
+        }
+
+        let reviewee: Address = if reviewer == agreement.user {
+            agreement.owner.clone()
+        } else if reviewer == agreement.owner {
+            agreement.user.clone()
+        } else {
+            return Err(Error::AgreementNotOwnedByCaller);
+        };
+
+        let review_key = DataKey::Review(agreement_id, reviewer.clone());
+        if get_persistent::<DataKey, Review>(&env, &review_key).is_some() {
+            return Err(Error::AlreadyReviewed);
+        }
+
+        let review: Review = Review {
+            reviewer: reviewer.clone(),
+            reviewee: reviewee.clone(),
+            rating,
+            review_hash,
+            timestamp: env.ledger().timestamp(),
+        };
+        store_persistent(&env, &review_key, &review);
+
+        add_rating(&env, &DataKey::Reputation(reviewee), rating);
+        if reviewer == agreement.user {
+            add_rating(&env, &DataKey::ListingReputation(agreement.listing_id), rating);
+        }
+
+        AgreementEvent::Reviewed(agreement_id, reviewer, rating).publish(&env);
+        Ok(())
+    }
+
+    fn get_review(env: Env, agreement_id: u64, reviewer: Address) -> Option<Review> {
+        get_persistent(&env, &DataKey::Review(agreement_id, reviewer))
+    }
+
+    fn get_reputation(env: Env, address: Address) -> Reputation {
+        get_reputation(&env, &DataKey::Reputation(address))
+    }
+
+    fn get_listing_reputation(env: Env, listing_id: u64) -> Reputation {
+        get_reputation(&env, &DataKey::ListingReputation(listing_id))
+    }
 }
+
+#[cfg(test)]
+mod test;
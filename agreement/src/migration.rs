@@ -0,0 +1,54 @@
+use common::agreement::types::{Agreement, DataKey};
+use soroban_sdk::{Env, Vec};
+use crate::schedule::store_agreement;
+use crate::storage::{extend_instance, get_persistent, store_persistent};
+
+// Merge an ID list left in instance storage by a release before agreements were persistent
+// into the persistent one, which may already hold agreements created since the upgrade
+fn merge_to_persistent(env: &Env, key: &DataKey) {
+    if let Some(mut merged) = env.storage().instance().get::<_, Vec<u64>>(key) {
+        env.storage().instance().remove(key);
+        let created: Vec<u64> = get_persistent(env, key).unwrap_or_else(|| Vec::new(env));
+        for id in created.iter() {
+            if !merged.contains(id) {
+                merged.push_back(id);
+            }
+        }
+        store_persistent(env, key, &merged);
+    }
+}
+
+// Move up to `limit` agreements and the user and listing lists they appear in from instance
+// to persistent storage. Live leases are indexed by end time on the way. Returns how many
+// agreements are left to look at.
+pub fn migrate_agreements(env: &Env, limit: u32) -> u64 {
+    let count: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::AgreementCount)
+        .unwrap_or(0);
+    let mut migrated: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::AgreementsMigrated)
+        .unwrap_or(0);
+    let last: u64 = count.min(migrated + limit as u64);
+
+    while migrated < last {
+        migrated += 1;
+        let agreement_key = DataKey::Agreement(migrated);
+        if let Some(agreement) = env.storage().instance().get::<_, Agreement>(&agreement_key) {
+            env.storage().instance().remove(&agreement_key);
+            store_agreement(env, &agreement);
+            merge_to_persistent(env, &DataKey::UserAgreements(agreement.user.clone()));
+            merge_to_persistent(env, &DataKey::ListingAgreements(agreement.listing_id));
+        }
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKey::AgreementsMigrated, &migrated);
+    extend_instance(env);
+
+    count - migrated
+}
@@ -0,0 +1,18 @@
+use common::agreement::types::{DataKey, Reputation};
+use soroban_sdk::Env;
+
+use crate::storage::{get_persistent, store_persistent};
+
+pub fn get_reputation(env: &Env, key: &DataKey) -> Reputation {
+    get_persistent(env, key).unwrap_or(Reputation {
+        rating_sum: 0,
+        review_count: 0,
+    })
+}
+
+pub fn add_rating(env: &Env, key: &DataKey, rating: u32) {
+    let mut reputation: Reputation = get_reputation(env, key);
+    reputation.rating_sum += rating as u64;
+    reputation.review_count += 1;
+    store_persistent(env, key, &reputation);
+}
@@ -0,0 +1,126 @@
+use common::agreement::types::{Agreement, AgreementStatus, AgreementType, DataKey, Subscription};
+use soroban_sdk::{Env, Vec};
+use crate::storage::{get_persistent, remove_persistent, store_persistent};
+
+// Leases and subscriptions are indexed by the hour their end time falls in, so the keeper
+// sweeps only the buckets that are due instead of every live agreement
+pub const BUCKET_SECONDS: u64 = 60 * 60;
+// Empty buckets a single sweep walks past, keeping one call within budget after a long gap
+pub const MAX_SCAN_BUCKETS: u64 = 64;
+
+fn bucket_of(time: u64) -> u64 {
+    time / BUCKET_SECONDS
+}
+
+// The time the keeper has to look at `agreement` again, if it's waiting on one. A subscription
+// in grace is only looked at again once its grace period is over.
+fn due_time(env: &Env, agreement: &Agreement) -> Option<u64> {
+    match (agreement.agreement_type, agreement.status) {
+        (AgreementType::Lease, AgreementStatus::Active)
+        | (AgreementType::Subscription, AgreementStatus::Active) => agreement.end_time,
+        (AgreementType::Subscription, AgreementStatus::Grace) => {
+            let subscription: Subscription =
+                get_persistent(env, &DataKey::Subscription(agreement.id))?;
+            agreement
+                .end_time
+                .map(|end_time| end_time + subscription.grace_period)
+        }
+        _ => None,
+    }
+}
+
+fn schedule(env: &Env, agreement: &Agreement) {
+    let time: u64 = match due_time(env, agreement) {
+        Some(time) => time,
+        None => return,
+    };
+    let agreement_type: AgreementType = agreement.agreement_type;
+    let bucket: u64 = bucket_of(time);
+
+    let key = DataKey::ExpiryBucket(agreement_type, bucket);
+    let mut ids: Vec<u64> = get_persistent(env, &key).unwrap_or_else(|| Vec::new(env));
+    if !ids.contains(agreement.id) {
+        ids.push_back(agreement.id);
+        store_persistent(env, &key, &ids);
+    }
+
+    // An earlier bucket than any scheduled so far moves the sweep back
+    let cursor_key = DataKey::ExpiryCursor(agreement_type);
+    let cursor: Option<u64> = get_persistent(env, &cursor_key);
+    if cursor.map_or(true, |cursor| bucket < cursor) {
+        store_persistent(env, &cursor_key, &bucket);
+    }
+}
+
+fn unschedule(env: &Env, agreement: &Agreement) {
+    let time: u64 = match due_time(env, agreement) {
+        Some(time) => time,
+        None => return,
+    };
+
+    let key = DataKey::ExpiryBucket(agreement.agreement_type, bucket_of(time));
+    let mut ids: Vec<u64> = get_persistent(env, &key).unwrap_or_else(|| Vec::new(env));
+    if let Some(index) = ids.first_index_of(agreement.id) {
+        ids.remove(index);
+        if ids.is_empty() {
+            remove_persistent(env, &key);
+        } else {
+            store_persistent(env, &key, &ids);
+        }
+    }
+}
+
+// Persist `agreement`, moving it to the bucket of its new end time or out of the index once
+// the keeper no longer has to look at it
+pub fn store_agreement(env: &Env, agreement: &Agreement) {
+    let key = DataKey::Agreement(agreement.id);
+    if let Some(previous) = get_persistent::<DataKey, Agreement>(env, &key) {
+        unschedule(env, &previous);
+    }
+    store_persistent(env, &key, agreement);
+    schedule(env, agreement);
+}
+
+// Up to `limit` agreements of `agreement_type` that are due, oldest bucket first.
+// Empty buckets behind the current time are skipped for good by moving the cursor past them.
+pub fn get_due(env: &Env, agreement_type: AgreementType, limit: u32) -> Vec<u64> {
+    let current_time: u64 = env.ledger().timestamp();
+    let cursor_key = DataKey::ExpiryCursor(agreement_type);
+    let mut due: Vec<u64> = Vec::new(env);
+
+    let mut bucket: u64 = match get_persistent(env, &cursor_key) {
+        Some(cursor) => cursor,
+        None => return due,
+    };
+    let start: u64 = bucket;
+    let last: u64 = bucket_of(current_time).min(start + MAX_SCAN_BUCKETS);
+    let mut cursor: u64 = start;
+
+    while bucket <= last && due.len() < limit {
+        let ids: Vec<u64> = get_persistent(env, &DataKey::ExpiryBucket(agreement_type, bucket))
+            .unwrap_or_else(|| Vec::new(env));
+        if ids.is_empty() && cursor == bucket && bucket < bucket_of(current_time) {
+            cursor = bucket + 1;
+        }
+
+        for agreement_id in ids.iter() {
+            if due.len() >= limit {
+                break;
+            }
+            let agreement: Option<Agreement> =
+                get_persistent(env, &DataKey::Agreement(agreement_id));
+            if agreement
+                .and_then(|agreement| due_time(env, &agreement))
+                .is_some_and(|time| time <= current_time)
+            {
+                due.push_back(agreement_id);
+            }
+        }
+        bucket += 1;
+    }
+
+    if cursor != start {
+        store_persistent(env, &cursor_key, &cursor);
+    }
+    due
+}
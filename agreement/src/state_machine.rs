@@ -0,0 +1,109 @@
+use common::access::{roles::has_role, types::Role};
+use common::agreement::types::{
+    Agreement, AgreementRole, AgreementStatus, AgreementTransition, DataKey, Error,
+};
+use soroban_sdk::{Address, Env, Vec};
+use crate::schedule::store_agreement;
+use crate::storage::{extend_persistent, get_persistent, has_persistent, store_persistent};
+
+// Every allowed (from, to, role) move. Anything not listed here is rejected.
+const TRANSITIONS: [(AgreementStatus, AgreementStatus, AgreementRole); 16] = [
+    // Rental handed over
+    (AgreementStatus::Created, AgreementStatus::Active, AgreementRole::Marketplace),
+    // Purchase receipt confirmed by the buyer
+    (AgreementStatus::Created, AgreementStatus::Completed, AgreementRole::User),
+    // Rental returned by either party, or subscription ended
+    (AgreementStatus::Active, AgreementStatus::Completed, AgreementRole::User),
+    (AgreementStatus::Active, AgreementStatus::Completed, AgreementRole::Owner),
+    (AgreementStatus::Active, AgreementStatus::Completed, AgreementRole::Marketplace),
+    // Sale or rental cancelled before hand over
+    (AgreementStatus::Created, AgreementStatus::Terminated, AgreementRole::Owner),
+    (AgreementStatus::Active, AgreementStatus::Paused, AgreementRole::User),
+    (AgreementStatus::Active, AgreementStatus::Paused, AgreementRole::Owner),
+    (AgreementStatus::Paused, AgreementStatus::Active, AgreementRole::User),
+    (AgreementStatus::Paused, AgreementStatus::Active, AgreementRole::Owner),
+    (AgreementStatus::Paused, AgreementStatus::Active, AgreementRole::Arbitrator),
+    // Subscription renewal failed, paid late, or not paid within the grace period
+    (AgreementStatus::Active, AgreementStatus::Grace, AgreementRole::Marketplace),
+    (AgreementStatus::Grace, AgreementStatus::Active, AgreementRole::Marketplace),
+    (AgreementStatus::Grace, AgreementStatus::Terminated, AgreementRole::Marketplace),
+    // Lease ran past its end time, then the owner confirmed the return
+    (AgreementStatus::Active, AgreementStatus::Overdue, AgreementRole::Marketplace),
+    (AgreementStatus::Overdue, AgreementStatus::Completed, AgreementRole::Owner),
+];
+
+pub fn is_allowed(from: AgreementStatus, to: AgreementStatus, role: AgreementRole) -> bool {
+    TRANSITIONS
+        .iter()
+        .any(|(f, t, r)| *f == from && *t == to && *r == role)
+}
+
+// Move `agreement` to `to`, persist it and append the move to its history
+pub fn transition(
+    env: &Env,
+    agreement: &mut Agreement,
+    to: AgreementStatus,
+    actor: &Address,
+    role: AgreementRole,
+) -> Result<(), Error> {
+    if !is_allowed(agreement.status, to, role) {
+        return Err(Error::InvalidTransition);
+    }
+
+    agreement.status = to;
+    store_agreement(env, agreement);
+    record_transition(env, agreement.id, to, actor, role);
+    extend_related(env, agreement);
+
+    Ok(())
+}
+
+// The terms and the user and listing indexes are only written around creation, keep them
+// live for as long as the agreement itself keeps moving
+fn extend_related(env: &Env, agreement: &Agreement) {
+    let keys = [
+        DataKey::AgreementTerms(agreement.id),
+        DataKey::UserAgreements(agreement.user.clone()),
+        DataKey::ListingAgreements(agreement.listing_id),
+    ];
+    for key in keys {
+        if has_persistent(env, &key) {
+            extend_persistent(env, &key);
+        }
+    }
+}
+
+pub fn record_transition(
+    env: &Env,
+    agreement_id: u64,
+    status: AgreementStatus,
+    actor: &Address,
+    role: AgreementRole,
+) {
+    let mut history: Vec<AgreementTransition> = get_history(env, agreement_id);
+    history.push_back(AgreementTransition {
+        status,
+        actor: actor.clone(),
+        role,
+        timestamp: env.ledger().timestamp(),
+    });
+    store_persistent(env, &DataKey::AgreementHistory(agreement_id), &history);
+}
+
+pub fn get_history(env: &Env, agreement_id: u64) -> Vec<AgreementTransition> {
+    get_persistent(env, &DataKey::AgreementHistory(agreement_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+// Role `address` holds on `agreement`, the parties taking precedence over arbitrators
+pub fn role_of(env: &Env, agreement: &Agreement, address: &Address) -> Option<AgreementRole> {
+    if *address == agreement.owner {
+        Some(AgreementRole::Owner)
+    } else if *address == agreement.user {
+        Some(AgreementRole::User)
+    } else if has_role(env, Role::Arbitrator, address) {
+        Some(AgreementRole::Arbitrator)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,12 @@
+use common::agreement::types::{DataKey, Error, Subscription};
+use soroban_sdk::Env;
+use crate::storage::{get_persistent, store_persistent};
+
+pub fn get_subscription(env: &Env, agreement_id: u64) -> Result<Subscription, Error> {
+    get_persistent(env, &DataKey::Subscription(agreement_id))
+        .ok_or(Error::NotASubscription)
+}
+
+pub fn store_subscription(env: &Env, agreement_id: u64, subscription: &Subscription) {
+    store_persistent(env, &DataKey::Subscription(agreement_id), subscription);
+}
@@ -0,0 +1,423 @@
+extern crate std;
+
+use super::{
+    schedule::{BUCKET_SECONDS, MAX_SCAN_BUCKETS},
+    state_machine::is_allowed,
+    AgreementContract,
+};
+use common::access::{interface::AccessControlClient, types::Role};
+use common::agreement::{
+    interface::AgreementContractClient,
+    types::{
+        Agreement, AgreementPage, AgreementRole, AgreementStatus, AgreementTransition,
+        AgreementType, Amendment, AmendmentStatus, DataKey, Error,
+    },
+};
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _, Ledger},
+    vec, Address, BytesN, Env,
+};
+
+struct AgreementTest<'a> {
+    env: Env,
+    admin: Address,
+    marketplace: Address,
+    owner: Address,
+    user: Address,
+    client: AgreementContractClient<'a>,
+}
+
+impl AgreementTest<'_> {
+    // The marketplace is a plain address here, mocked auths stand in for its calls
+    fn setup() -> Self {
+        let env: Env = Env::default();
+        env.mock_all_auths();
+
+        let admin: Address = Address::generate(&env);
+        let marketplace: Address = Address::generate(&env);
+        let contract_id: Address = env.register(AgreementContract, ());
+        let client = AgreementContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &marketplace);
+
+        AgreementTest {
+            owner: Address::generate(&env),
+            user: Address::generate(&env),
+            env,
+            admin,
+            marketplace,
+            client,
+        }
+    }
+
+    // A lease on listing 1 that has been handed over and is running
+    fn active_lease(&self, duration: u64) -> u64 {
+        let agreement_id: u64 = self.client.create_agreement(
+            &1u64,
+            &self.user,
+            &self.owner,
+            &0u32,
+            &true,
+            &duration,
+        );
+        let terms_hash: BytesN<32> = BytesN::from_array(&self.env, &[1u8; 32]);
+        self.client
+            .attach_terms(&agreement_id, &self.owner, &terms_hash, &1u32);
+        self.client.sign_terms(&agreement_id, &self.owner, &terms_hash);
+        self.client.sign_terms(&agreement_id, &self.user, &terms_hash);
+        self.client.owner_fulfilled(&agreement_id);
+        agreement_id
+    }
+}
+
+#[test]
+fn test_transitions_recorded_with_actor_role() {
+    let test = AgreementTest::setup();
+    let arbitrator: Address = Address::generate(&test.env);
+    AccessControlClient::new(&test.env, &test.client.address)
+        .grant_role(&test.admin, &Role::Arbitrator, &arbitrator);
+
+    let agreement_id: u64 = test.active_lease(3600);
+    assert_eq!(
+        test.client.try_owner_fulfilled(&agreement_id),
+        Err(Ok(Error::AgreementNotActive))
+    );
+    assert_eq!(
+        test.client
+            .try_terminate_agreement(&agreement_id, &test.owner),
+        Err(Ok(Error::AgreementIsAlreadyActive))
+    );
+
+    // The user pauses, an arbitrator resumes, and the owner completes
+    test.env.ledger().set_timestamp(100);
+    test.client.pause_agreement(&agreement_id, &test.user);
+    assert_eq!(
+        test.client
+            .try_resume_agreement(&agreement_id, &Address::generate(&test.env)),
+        Err(Ok(Error::AgreementNotOwnedByCaller))
+    );
+    test.env.ledger().set_timestamp(150);
+    assert_eq!(test.client.resume_agreement(&agreement_id, &arbitrator), 3650);
+    test.client.complete_agreement(&agreement_id, &test.owner);
+
+    let history: soroban_sdk::Vec<AgreementTransition> =
+        test.client.get_agreement_history(&agreement_id);
+    let expected = [
+        (AgreementStatus::Created, &test.marketplace, AgreementRole::Marketplace, 0),
+        (AgreementStatus::Active, &test.marketplace, AgreementRole::Marketplace, 0),
+        (AgreementStatus::Paused, &test.user, AgreementRole::User, 100),
+        (AgreementStatus::Active, &arbitrator, AgreementRole::Arbitrator, 150),
+        (AgreementStatus::Completed, &test.owner, AgreementRole::Owner, 150),
+    ];
+    assert_eq!(history.len(), expected.len() as u32);
+    for (entry, (status, actor, role, timestamp)) in history.iter().zip(expected) {
+        assert!(entry.status == status && entry.role == role);
+        assert_eq!(&entry.actor, actor);
+        assert_eq!(entry.timestamp, timestamp);
+    }
+
+    // Nothing leaves a completed agreement
+    assert!(!is_allowed(
+        AgreementStatus::Completed,
+        AgreementStatus::Active,
+        AgreementRole::Owner
+    ));
+    assert!(!is_allowed(
+        AgreementStatus::Created,
+        AgreementStatus::Completed,
+        AgreementRole::Owner
+    ));
+    assert_eq!(
+        test.client.try_complete_agreement(&agreement_id, &test.user),
+        Err(Ok(Error::InvalidTransition))
+    );
+}
+
+#[test]
+fn test_amendment_keeps_paused_time() {
+    let test = AgreementTest::setup();
+    let agreement_id: u64 = test.active_lease(3600);
+    test.env.ledger().set_timestamp(100);
+    test.client.pause_agreement(&agreement_id, &test.user);
+    test.env.ledger().set_timestamp(150);
+    test.client.resume_agreement(&agreement_id, &test.owner);
+
+    let version: u32 =
+        test.client
+            .propose_amendment(&agreement_id, &test.user, &Some(7200u64), &None, &500i128);
+    assert_eq!(version, 1);
+    assert_eq!(
+        test.client
+            .try_propose_amendment(&agreement_id, &test.owner, &None, &Some(9000u64), &0i128),
+        Err(Ok(Error::AmendmentPending))
+    );
+    assert!(
+        test.client
+            .try_accept_amendment(&agreement_id, &test.user, &version)
+            == Err(Ok(Error::AgreementNotOwnedByCaller))
+    );
+    assert!(
+        test.client
+            .accept_amendment(&agreement_id, &test.owner, &version)
+            == AmendmentStatus::Accepted
+    );
+    assert_eq!(
+        test.client
+            .try_reject_amendment(&agreement_id, &test.user, &version),
+        Err(Ok(Error::InvalidAmendment))
+    );
+
+    // The new duration moves the end time by the difference, keeping the paused 50 seconds
+    let amendment: Amendment = test.client.settle_amendment(&agreement_id, &version);
+    assert!(amendment.status == AmendmentStatus::Applied);
+    let agreement: Agreement = test.client.get_agreement(&agreement_id);
+    assert_eq!(agreement.duration, Some(7200));
+    assert_eq!(agreement.end_time, Some(7250));
+    assert_eq!(
+        test.client
+            .try_settle_amendment(&agreement_id, &version)
+            .err(),
+        Some(Ok(Error::InvalidAmendment))
+    );
+
+    // A rejected proposal leaves the agreement as it was
+    let version: u32 =
+        test.client
+            .propose_amendment(&agreement_id, &test.owner, &None, &Some(9000u64), &0i128);
+    assert_eq!(version, 2);
+    test.client
+        .reject_amendment(&agreement_id, &test.user, &version);
+    assert_eq!(
+        test.client.get_agreement(&agreement_id).end_time,
+        Some(7250)
+    );
+    assert_eq!(test.client.get_amendments(&agreement_id).len(), 2);
+    assert_eq!(
+        test.client.try_get_amendment(&agreement_id, &0u32).err(),
+        Some(Ok(Error::AmendmentNotFound))
+    );
+}
+
+#[test]
+fn test_terms_signed_before_start() {
+    let test = AgreementTest::setup();
+    let agreement_id: u64 =
+        test.client
+            .create_agreement(&1u64, &test.user, &test.owner, &0u32, &true, &3600u64);
+    let terms_hash: BytesN<32> = BytesN::from_array(&test.env, &[1u8; 32]);
+    let other_hash: BytesN<32> = BytesN::from_array(&test.env, &[2u8; 32]);
+
+    assert_eq!(
+        test.client.try_owner_fulfilled(&agreement_id),
+        Err(Ok(Error::TermsNotFound))
+    );
+
+    // Either side may replace its own proposal until the other side signs
+    test.client
+        .attach_terms(&agreement_id, &test.user, &terms_hash, &1u32);
+    test.client.sign_terms(&agreement_id, &test.user, &terms_hash);
+    test.client
+        .attach_terms(&agreement_id, &test.user, &other_hash, &2u32);
+    assert!(test.client.get_terms_signers(&agreement_id).is_empty());
+    test.client.sign_terms(&agreement_id, &test.owner, &other_hash);
+    assert_eq!(
+        test.client
+            .try_attach_terms(&agreement_id, &test.user, &terms_hash, &3u32),
+        Err(Ok(Error::TermsAlreadySigned))
+    );
+    assert_eq!(
+        test.client.try_owner_fulfilled(&agreement_id),
+        Err(Ok(Error::TermsNotAccepted))
+    );
+
+    assert!(test.client.sign_terms(&agreement_id, &test.user, &other_hash));
+    assert!(test.client.owner_fulfilled(&agreement_id));
+}
+
+#[test]
+fn test_ttl_extended_by_writes_not_reads() {
+    let test = AgreementTest::setup();
+    let agreement_id: u64 =
+        test.client
+            .create_agreement(&1u64, &test.user, &test.owner, &0u32, &true, &3600u64);
+    let terms_hash: BytesN<32> = BytesN::from_array(&test.env, &[1u8; 32]);
+    test.client
+        .attach_terms(&agreement_id, &test.owner, &terms_hash, &1u32);
+    test.client.sign_terms(&agreement_id, &test.owner, &terms_hash);
+    test.client.sign_terms(&agreement_id, &test.user, &terms_hash);
+
+    let user_key = DataKey::UserAgreements(test.user.clone());
+    let ttl = |key: &DataKey| {
+        test.env
+            .as_contract(&test.client.address, || test.env.storage().persistent().get_ttl(key))
+    };
+    let max_ttl: u32 = ttl(&user_key);
+
+    // Two weeks on, reading doesn't touch the TTL
+    test.env
+        .ledger()
+        .with_mut(|ledger| ledger.sequence_number += 2 * 120_960);
+    test.client.get_agreement(&agreement_id);
+    test.client
+        .get_user_agreements(&test.user, &None, &None, &0u32, &10u32);
+    assert_eq!(ttl(&user_key), max_ttl - 2 * 120_960);
+
+    // A transition keeps the agreement and what it depends on live
+    test.client.owner_fulfilled(&agreement_id);
+    assert_eq!(ttl(&DataKey::Agreement(agreement_id)), max_ttl);
+    assert_eq!(ttl(&DataKey::AgreementTerms(agreement_id)), max_ttl);
+    assert_eq!(ttl(&user_key), max_ttl);
+    assert_eq!(ttl(&DataKey::ListingAgreements(1)), max_ttl);
+}
+
+#[test]
+fn test_expiry_index_follows_end_time() {
+    let test = AgreementTest::setup();
+    let short: u64 = test.active_lease(BUCKET_SECONDS);
+    let long: u64 = test.active_lease(10 * BUCKET_SECONDS);
+    let far: u64 = test.active_lease((MAX_SCAN_BUCKETS + 80) * BUCKET_SECONDS);
+
+    test.env.ledger().set_timestamp(BUCKET_SECONDS - 1);
+    assert!(test.client.get_expired_leases(&10u32).is_empty());
+    test.env.ledger().set_timestamp(BUCKET_SECONDS);
+    assert_eq!(test.client.get_expired_leases(&10u32), vec![&test.env, short]);
+
+    // A paused lease leaves the index and comes back at its pushed back end time
+    test.client.pause_agreement(&short, &test.user);
+    assert!(test.client.get_expired_leases(&10u32).is_empty());
+    test.env.ledger().set_timestamp(BUCKET_SECONDS + 100);
+    assert_eq!(
+        test.client.resume_agreement(&short, &test.user),
+        BUCKET_SECONDS + 100
+    );
+    assert_eq!(test.client.get_expired_leases(&10u32), vec![&test.env, short]);
+    test.client.mark_expired(&short);
+    assert!(test.client.get_expired_leases(&10u32).is_empty());
+
+    // After a long gap each sweep walks a bounded number of buckets
+    test.env.ledger().set_timestamp(3 * MAX_SCAN_BUCKETS * BUCKET_SECONDS);
+    assert_eq!(test.client.get_expired_leases(&10u32), vec![&test.env, long]);
+    test.client.mark_expired(&long);
+    assert!(test.client.get_expired_leases(&10u32).is_empty());
+    assert!(test.client.get_expired_leases(&10u32).is_empty());
+    assert_eq!(test.client.get_expired_leases(&1u32), vec![&test.env, far]);
+}
+
+#[test]
+fn test_grace_renewals_due_at_deadline() {
+    let test = AgreementTest::setup();
+    let period: u64 = BUCKET_SECONDS;
+    let grace_period: u64 = 2 * BUCKET_SECONDS;
+    let first: u64 = test.client.create_subscription(
+        &1u64,
+        &test.user,
+        &test.owner,
+        &period,
+        &100_i128,
+        &grace_period,
+    );
+    let second: u64 = test.client.create_subscription(
+        &1u64,
+        &test.user,
+        &test.owner,
+        &(2 * period),
+        &100_i128,
+        &grace_period,
+    );
+
+    test.env.ledger().set_timestamp(period);
+    assert_eq!(test.client.get_due_subscriptions(&1u32), vec![&test.env, first]);
+    assert!(test.client.record_renewal(&first, &false) == AgreementStatus::Grace);
+
+    // In grace the first one waits for its deadline instead of taking the only slot
+    test.env.ledger().set_timestamp(2 * period);
+    assert_eq!(test.client.get_due_subscriptions(&1u32), vec![&test.env, second]);
+    assert!(test.client.record_renewal(&second, &true) == AgreementStatus::Active);
+    assert!(test.client.get_due_subscriptions(&10u32).is_empty());
+
+    test.env.ledger().set_timestamp(period + grace_period);
+    assert_eq!(test.client.get_due_subscriptions(&10u32), vec![&test.env, first]);
+
+    // Too late to pay once the grace period is over
+    test.env.ledger().set_timestamp(period + grace_period + 1);
+    assert!(test.client.try_record_renewal(&first, &true) == Err(Ok(Error::GracePeriodOver)));
+    assert!(test.client.record_renewal(&first, &false) == AgreementStatus::Terminated);
+    assert!(test.client.get_due_subscriptions(&10u32).is_empty());
+}
+
+#[test]
+fn test_migrate_version_one_storage() {
+    let test = AgreementTest::setup();
+    let lease: Agreement = Agreement {
+        id: 1,
+        user: test.user.clone(),
+        owner: test.owner.clone(),
+        listing_id: 7,
+        timestamp: 0,
+        shares: 0,
+        duration: Some(3600),
+        end_time: Some(3600),
+        status: AgreementStatus::Active,
+        agreement_type: AgreementType::Lease,
+    };
+    let purchase: Agreement = Agreement {
+        id: 2,
+        duration: None,
+        end_time: None,
+        shares: 10,
+        status: AgreementStatus::Completed,
+        agreement_type: AgreementType::Purchase,
+        ..lease.clone()
+    };
+
+    // Version 1 kept everything in instance storage
+    test.env.as_contract(&test.client.address, || {
+        let storage = test.env.storage().instance();
+        storage.set(&DataKey::Agreement(1), &lease);
+        storage.set(&DataKey::Agreement(2), &purchase);
+        storage.set(&DataKey::UserAgreements(test.user.clone()), &vec![&test.env, 1u64, 2u64]);
+        storage.set(&DataKey::ListingAgreements(7), &vec![&test.env, 1u64, 2u64]);
+        storage.set(&DataKey::AgreementCount, &2u64);
+    });
+    assert!(test.client.try_get_agreement(&1u64).is_err());
+
+    // Agreements created between the upgrade and the migration stay indexed
+    assert_eq!(
+        test.client
+            .create_agreement(&7u64, &test.user, &test.owner, &10u32, &false, &0u64),
+        3
+    );
+
+    assert_eq!(test.client.migrate(&test.admin, &1u32), 2);
+    assert_eq!(test.client.get_agreement(&1u64).end_time, Some(3600));
+    assert!(test.client.try_get_agreement(&2u64).is_err());
+    assert_eq!(test.client.migrate(&test.admin, &10u32), 0);
+    assert_eq!(test.client.migrate(&test.admin, &10u32), 0);
+
+    assert_eq!(test.client.get_agreement(&2u64).shares, 10);
+    let page: AgreementPage =
+        test.client
+            .get_user_agreements(&test.user, &None, &None, &0u32, &10u32);
+    assert_eq!(page.agreements.len(), 3);
+    assert_eq!(page.agreements.get(2).unwrap().id, 3);
+    let page: AgreementPage =
+        test.client
+            .get_listing_agreements(&7u64, &Some(AgreementStatus::Completed), &None, &0u32, &10u32);
+    assert_eq!(page.agreements.get(0).unwrap().id, 2);
+    test.env.as_contract(&test.client.address, || {
+        let storage = test.env.storage().instance();
+        assert!(!storage.has(&DataKey::Agreement(1)));
+        assert!(!storage.has(&DataKey::UserAgreements(test.user.clone())));
+        assert!(!storage.has(&DataKey::ListingAgreements(7)));
+    });
+
+    // Only the live lease is picked up by the expiry sweep
+    test.env.ledger().set_timestamp(3600);
+    assert_eq!(test.client.get_expired_leases(&10u32), vec![&test.env, 1u64]);
+
+    // New agreements carry on from the migrated count
+    assert_eq!(
+        test.client
+            .create_agreement(&7u64, &test.user, &test.owner, &0u32, &true, &3600u64),
+        4
+    );
+}
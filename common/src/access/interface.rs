@@ -0,0 +1,63 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+use super::types::Role;
+
+#[contractclient(name = "AccessControlClient")]
+pub trait AccessControl {
+    fn grant_role(env: Env, caller: Address, role: Role, account: Address);
+    fn revoke_role(env: Env, caller: Address, role: Role, account: Address);
+    fn renounce_role(env: Env, account: Address, role: Role);
+    fn has_role(env: Env, role: Role, account: Address) -> bool;
+    fn bootstrap_roles(env: Env);
+}
+
+// Export `AccessControl` from `$contract`. `$admin` reads the admin the contract stored before
+// roles existed, so a contract deployed without them can seed them once upgraded.
+#[macro_export]
+macro_rules! impl_access_control {
+    ($contract:ident, $admin:expr) => {
+        use $crate::access::interface::AccessControl as _;
+
+        #[soroban_sdk::contractimpl]
+        impl $crate::access::interface::AccessControl for $contract {
+            fn grant_role(
+                env: soroban_sdk::Env,
+                caller: soroban_sdk::Address,
+                role: $crate::access::types::Role,
+                account: soroban_sdk::Address,
+            ) {
+                $crate::access::roles::grant_role(&env, &caller, role, &account);
+            }
+
+            fn revoke_role(
+                env: soroban_sdk::Env,
+                caller: soroban_sdk::Address,
+                role: $crate::access::types::Role,
+                account: soroban_sdk::Address,
+            ) {
+                $crate::access::roles::revoke_role(&env, &caller, role, &account);
+            }
+
+            fn renounce_role(
+                env: soroban_sdk::Env,
+                account: soroban_sdk::Address,
+                role: $crate::access::types::Role,
+            ) {
+                $crate::access::roles::renounce_role(&env, &account, role);
+            }
+
+            fn has_role(
+                env: soroban_sdk::Env,
+                role: $crate::access::types::Role,
+                account: soroban_sdk::Address,
+            ) -> bool {
+                $crate::access::roles::has_role(&env, role, &account)
+            }
+
+            fn bootstrap_roles(env: soroban_sdk::Env) {
+                let admin: soroban_sdk::Address = ($admin)(&env);
+                $crate::access::roles::bootstrap_roles(&env, &admin);
+            }
+        }
+    };
+}
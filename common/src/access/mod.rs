@@ -0,0 +1,3 @@
+pub mod interface;
+pub mod roles;
+pub mod types;
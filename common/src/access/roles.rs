@@ -0,0 +1,89 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use super::types::{AccessError, AccessKey, Role};
+
+// Make `admin` the first admin, called once from a contract's initializer
+pub fn init_admin(env: &Env, admin: &Address) {
+    let storage = env.storage().instance();
+    storage.set(&AccessKey::Role(Role::Admin, admin.clone()), &true);
+    storage.set(&AccessKey::AdminCount, &1u32);
+}
+
+// Seed the roles of a contract deployed before roles existed from the admin it stored, so it
+// isn't left without anyone to administer or upgrade it once upgraded. Runs once.
+pub fn bootstrap_roles(env: &Env, admin: &Address) {
+    if env.storage().instance().has(&AccessKey::AdminCount) {
+        panic_with_error!(env, AccessError::AlreadyBootstrapped);
+    }
+    admin.require_auth();
+
+    init_admin(env, admin);
+    env.storage()
+        .instance()
+        .set(&AccessKey::Role(Role::Upgrader, admin.clone()), &true);
+}
+
+fn holds(env: &Env, role: Role, account: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&AccessKey::Role(role, account.clone()))
+}
+
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    holds(env, role, account) || holds(env, Role::Admin, account)
+}
+
+// Panics unless `account` authorized the call and holds `role`
+pub fn require_role(env: &Env, role: Role, account: &Address) {
+    account.require_auth();
+    if !has_role(env, role, account) {
+        panic_with_error!(env, AccessError::Unauthorized);
+    }
+}
+
+pub fn grant_role(env: &Env, caller: &Address, role: Role, account: &Address) {
+    require_role(env, Role::Admin, caller);
+    if holds(env, role, account) {
+        return;
+    }
+
+    let storage = env.storage().instance();
+    storage.set(&AccessKey::Role(role, account.clone()), &true);
+    if role == Role::Admin {
+        let count: u32 = storage.get(&AccessKey::AdminCount).unwrap_or(0);
+        storage.set(&AccessKey::AdminCount, &(count + 1));
+    }
+
+    env.events()
+        .publish(("role_granted", role), account.clone());
+}
+
+pub fn revoke_role(env: &Env, caller: &Address, role: Role, account: &Address) {
+    require_role(env, Role::Admin, caller);
+    remove_role(env, role, account);
+}
+
+pub fn renounce_role(env: &Env, account: &Address, role: Role) {
+    account.require_auth();
+    remove_role(env, role, account);
+}
+
+// The last admin can't be removed, or the contract could never be administered again
+fn remove_role(env: &Env, role: Role, account: &Address) {
+    if !holds(env, role, account) {
+        return;
+    }
+
+    let storage = env.storage().instance();
+    if role == Role::Admin {
+        let count: u32 = storage.get(&AccessKey::AdminCount).unwrap_or(0);
+        if count <= 1 {
+            panic_with_error!(env, AccessError::LastAdmin);
+        }
+        storage.set(&AccessKey::AdminCount, &(count - 1));
+    }
+    storage.remove(&AccessKey::Role(role, account.clone()));
+
+    env.events()
+        .publish(("role_revoked", role), account.clone());
+}
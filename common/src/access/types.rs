@@ -0,0 +1,29 @@
+use soroban_sdk::{contracterror, contracttype, Address};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccessError {
+    Unauthorized = 100,
+    LastAdmin = 101,
+    AlreadyBootstrapped = 102,
+}
+
+// Admins hold every other role implicitly
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum Role {
+    Admin = 1,
+    Pauser = 2,
+    Upgrader = 3,
+    FeeManager = 4,
+    OracleManager = 5,
+    Arbitrator = 6,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum AccessKey {
+    Role(Role, Address),
+    AdminCount,
+}
@@ -1,12 +1,22 @@
 use soroban_sdk::{contractclient, Address, BytesN, Env, Symbol, Vec};
-use super::types::{Agreement, AgreementStatus, Error};
+use super::types::{
+    Agreement, AgreementPage, AgreementPause, AgreementStatus, AgreementTerms,
+    AgreementTransition, AgreementType, Amendment, AmendmentStatus, Error, Reputation, Review,
+    Subscription,
+};
 
 #[contractclient(name = "AgreementContractClient")]
 pub trait AgreementContractTrait {
     fn initialize(env: Env, admin: Address, marketplace_contract_id: Address) -> Result<(), Error>;
     fn version() -> u32;
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error>;
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
+    fn migrate(env: Env, caller: Address, limit: u32) -> u64;
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error>;
     fn create_agreement(
         env: Env,
         listing_id: u64,
@@ -17,11 +27,92 @@ pub trait AgreementContractTrait {
         duration: u64,
     ) -> u64;
     fn get_agreement(env: Env, agreement_id: u64) -> Result<Agreement, Error>;
-    fn get_user_agreements(env: Env, user: Address) -> Vec<u64>;
-    fn get_listing_agreements(env: Env, listing_id: u64) -> Vec<u64>;
+    fn get_user_agreements(
+        env: Env,
+        user: Address,
+        status: Option<AgreementStatus>,
+        agreement_type: Option<AgreementType>,
+        cursor: u32,
+        limit: u32,
+    ) -> AgreementPage;
+    fn get_listing_agreements(
+        env: Env,
+        listing_id: u64,
+        status: Option<AgreementStatus>,
+        agreement_type: Option<AgreementType>,
+        cursor: u32,
+        limit: u32,
+    ) -> AgreementPage;
     fn get_agreement_status(env: Env, agreement_id: u64) -> Result<AgreementStatus, Error>;
     fn owner_fulfilled(env: Env, agreement_id: u64) -> Result<bool, Error>;
     fn complete_agreement(env: Env, agreement_id: u64, user: Address) -> Result<bool, Error>;
     fn terminate_agreement(env: Env, agreement_id: u64, terminator: Address)
         -> Result<bool, Error>;
+    fn pause_agreement(env: Env, agreement_id: u64, caller: Address) -> Result<(), Error>;
+    fn resume_agreement(env: Env, agreement_id: u64, caller: Address) -> Result<u64, Error>;
+    fn get_agreement_pause(env: Env, agreement_id: u64) -> Option<AgreementPause>;
+    fn get_agreement_history(env: Env, agreement_id: u64) -> Vec<AgreementTransition>;
+    fn attach_terms(
+        env: Env,
+        agreement_id: u64,
+        party: Address,
+        terms_hash: BytesN<32>,
+        version: u32,
+    ) -> Result<(), Error>;
+    fn sign_terms(
+        env: Env,
+        agreement_id: u64,
+        signer: Address,
+        terms_hash: BytesN<32>,
+    ) -> Result<bool, Error>;
+    fn get_agreement_terms(env: Env, agreement_id: u64) -> Option<AgreementTerms>;
+    fn get_terms_signers(env: Env, agreement_id: u64) -> Vec<Address>;
+    fn propose_amendment(
+        env: Env,
+        agreement_id: u64,
+        proposer: Address,
+        duration: Option<u64>,
+        end_time: Option<u64>,
+        price_delta: i128,
+    ) -> Result<u32, Error>;
+    fn accept_amendment(
+        env: Env,
+        agreement_id: u64,
+        accepter: Address,
+        version: u32,
+    ) -> Result<AmendmentStatus, Error>;
+    fn reject_amendment(
+        env: Env,
+        agreement_id: u64,
+        caller: Address,
+        version: u32,
+    ) -> Result<(), Error>;
+    fn settle_amendment(env: Env, agreement_id: u64, version: u32) -> Result<Amendment, Error>;
+    fn get_amendment(env: Env, agreement_id: u64, version: u32) -> Result<Amendment, Error>;
+    fn get_amendments(env: Env, agreement_id: u64) -> Vec<Amendment>;
+    fn create_subscription(
+        env: Env,
+        listing_id: u64,
+        user: Address,
+        owner: Address,
+        period: u64,
+        price: i128,
+        grace_period: u64,
+    ) -> u64;
+    fn get_subscription(env: Env, agreement_id: u64) -> Result<Subscription, Error>;
+    fn get_due_subscriptions(env: Env, limit: u32) -> Vec<u64>;
+    fn record_renewal(env: Env, agreement_id: u64, paid: bool) -> Result<AgreementStatus, Error>;
+    fn cancel_subscription(env: Env, agreement_id: u64, user: Address) -> Result<(), Error>;
+    fn get_expired_leases(env: Env, limit: u32) -> Vec<u64>;
+    fn mark_expired(env: Env, agreement_id: u64) -> Result<(), Error>;
+    fn leave_review(
+        env: Env,
+        agreement_id: u64,
+        reviewer: Address,
+        rating: u32,
+        review_hash: BytesN<32>,
+    ) -> Result<(), Error>;
+    fn get_review(env: Env, agreement_id: u64, reviewer: Address) -> Option<Review>;
+    fn get_reputation(env: Env, address: Address) -> Reputation;
+    fn get_listing_reputation(env: Env, listing_id: u64) -> Reputation;
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Symbol};
+use soroban_sdk::{contracterror, contracttype, symbol_short, Address, BytesN, Symbol, Vec};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -14,7 +14,24 @@ pub enum Error {
     AgreementNotActive = 8,
     AgreementNotOwnedByCaller = 9,
     AgreementIsAlreadyActive = 10,
-    StateNotAlreadySet = 11
+    StateNotAlreadySet = 11,
+    AgreementPaused = 12,
+    AgreementNotPaused = 13,
+    InvalidTransition = 14,
+    TermsNotFound = 15,
+    TermsMismatch = 16,
+    TermsNotAccepted = 17,
+    AmendmentNotFound = 18,
+    AmendmentPending = 19,
+    InvalidAmendment = 20,
+    NotASubscription = 21,
+    SubscriptionNotDue = 22,
+    AgreementNotExpired = 23,
+    AgreementNotCompleted = 24,
+    AlreadyReviewed = 25,
+    InvalidRating = 26,
+    GracePeriodOver = 27,
+    TermsAlreadySigned = 28,
 }
 
 #[derive(Clone)]
@@ -24,6 +41,17 @@ pub enum DataKey {
     AgreementCount,
     UserAgreements(Address),         // List of agreement IDs for a user
     ListingAgreements(u64),          // List of agreement IDs for a listing
+    AgreementPause(u64),             // Who paused an agreement and when
+    AgreementHistory(u64),           // Status transitions of an agreement, oldest first
+    AgreementTerms(u64),             // Off-chain terms document and who accepted it
+    Amendments(u64),                 // Amendments of an agreement, by version
+    Subscription(u64),               // Renewal terms of a subscription agreement
+    ExpiryBucket(AgreementType, u64), // Agreements due in an hour-long bucket of end times
+    ExpiryCursor(AgreementType),     // Earliest bucket the keeper may still find agreements in
+    AgreementsMigrated,              // Last agreement ID moved out of instance storage
+    Review(u64, Address),            // Review a party left on a completed agreement
+    Reputation(Address),             // Ratings an address received as a party to agreements
+    ListingReputation(u64),          // Ratings users gave the owner of a listing
 }
 
 #[derive(Clone)]
@@ -41,11 +69,68 @@ pub struct Agreement {
     pub agreement_type: AgreementType
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct AgreementPage {
+    pub agreements: Vec<Agreement>,
+    pub next_cursor: Option<u32>, // Pass back as `cursor` to get the next page, `None` at the end
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AgreementPause {
+    pub paused_by: Address,
+    pub paused_at: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AgreementTerms {
+    pub terms_hash: BytesN<32>, // Hash of the off-chain terms document
+    pub version: u32,
+    pub user_signed_at: Option<u64>,
+    pub owner_signed_at: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum AmendmentStatus {
+    Proposed = 1,
+    Accepted = 2, // Waiting for the marketplace to settle the price delta
+    Applied = 3,
+    Rejected = 4,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Amendment {
+    pub version: u32,
+    pub proposer: Address,
+    pub duration: Option<u64>,
+    pub end_time: Option<u64>,
+    pub price_delta: i128, // Paid by the user when positive, refunded when negative
+    pub status: AmendmentStatus,
+    pub proposed_at: u64,
+    pub updated_at: u64,
+}
+
+// Renews every `period` seconds at the agreement's `end_time`
+#[derive(Clone)]
+#[contracttype]
+pub struct Subscription {
+    pub period: u64,
+    pub price: i128, // Per period, in listing currency
+    pub grace_period: u64,
+    pub renewals: u32,
+    pub cancelled: bool, // Ends at the current `end_time` instead of renewing
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[contracttype]
 pub enum AgreementType {
     Lease,
-    Purchase
+    Purchase,
+    Subscription,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -56,7 +141,58 @@ pub enum AgreementStatus {
     Completed = 3,
     Terminated = 4,
     Paused = 5,
+    Grace = 6, // Subscription renewal failed, access kept until the grace period ends
+    Overdue = 7, // Lease ended but the owner hasn't confirmed the return yet
+}
+
+// Capacity in which an address moves an agreement between statuses
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[contracttype]
+pub enum AgreementRole {
+    Marketplace = 1,
+    User = 2,
+    Owner = 3,
+    Arbitrator = 4,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AgreementTransition {
+    pub status: AgreementStatus, // Status entered
+    pub actor: Address,
+    pub role: AgreementRole,
+    pub timestamp: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Review {
+    pub reviewer: Address,
+    pub reviewee: Address,       // The other party to the agreement
+    pub rating: u32,             // MIN_RATING to MAX_RATING
+    pub review_hash: BytesN<32>, // Hash of the off-chain review text
+    pub timestamp: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Reputation {
+    pub rating_sum: u64,
+    pub review_count: u32,
+}
+
+impl Reputation {
+    // Mean rating in hundredths, e.g. 450 for 4.5; 0 without reviews
+    pub fn average(&self) -> u32 {
+        if self.review_count == 0 {
+            return 0;
+        }
+        (self.rating_sum * 100 / self.review_count as u64) as u32
+    }
 }
 
 pub const ADMIN: Symbol = symbol_short!("ADMIN");
 pub const MARKETPLACE_CONTRACT: Symbol = symbol_short!("MAR_CA");
+pub const MAX_PAGE_SIZE: u32 = 50;
+pub const MIN_RATING: u32 = 1;
+pub const MAX_RATING: u32 = 5;
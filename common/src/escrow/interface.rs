@@ -5,8 +5,13 @@ use super::types::{EscrowError as Error, Escrow, EscrowStatus};
 pub trait EscrowContractTrait {
     fn initialize(env: Env, admin: Address, marketplace_contract_id: Address) -> Result<(), Error>;
     fn version() -> u32;
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error>;
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error>;
     fn get_escrow(env: Env, listing_id: u64) -> Result<Escrow, Error>;
     fn status(env: Env, listing_id: u64) -> Result<EscrowStatus, Error>;
     fn lock_funds(
@@ -19,4 +24,5 @@ pub trait EscrowContractTrait {
     ) -> Result<(), Error>;
     fn release(env: Env, listing_id: u64) -> Result<i128, Error>;
     fn refund(env: Env, listing_id: u64) -> Result<(), Error>;
+    fn adjust_amount(env: Env, listing_id: u64, delta: i128) -> Result<i128, Error>;
 }
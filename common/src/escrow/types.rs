@@ -8,6 +8,7 @@ pub enum EscrowError {
     EscrowNotActive = 2,
     EscrowNotFound = 3,
     AlreadyInitialized = 4,
+    InsufficientFunds = 5,
 }
 
 #[derive(Clone)]
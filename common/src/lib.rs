@@ -1,6 +1,7 @@
 #![no_std]
 #![allow(clippy::unused_unit)]
 
+pub mod access;
 pub mod agreement;
 pub mod escrow;
 pub mod nft;
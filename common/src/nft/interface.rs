@@ -1,5 +1,5 @@
 use soroban_sdk::{contractclient, Address, BytesN, Env, Map, String, Symbol, Vec};
-use super::types::{Error, TokenMetadata};
+use super::types::{Error, ProvenancePage, ShareSchedule, TokenMetadata};
 
 #[contractclient(name = "NFTContractClient")]
 pub trait NFTInterface {
@@ -9,26 +9,95 @@ pub trait NFTInterface {
         marketplace_contract_id: Address,
     ) -> Result<(), Error>;
     fn version() -> u32;
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error>;
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
+    fn migrate(env: Env, caller: Address, limit: u32) -> u64;
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error>;
     fn symbol(env: Env) -> String;
     fn name(env: Env) -> String;
     fn mint(env: Env, owner: Address, token_id: u64, shares: u32, token_uri: String) -> u64;
     fn owners_of(env: Env, token_id: u64) -> Vec<Address>;
-    fn transfer(env: Env, from: Address, to: Address, token_id: u64) -> bool;
-    fn transfer_shares(env: Env, from: Address, to: Address, token_id: u64, shares: u32) -> bool;
+    fn transfer(env: Env, from: Address, to: Address, token_id: u64, agreement_id: u64) -> bool;
+    fn transfer_shares(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        shares: u32,
+    ) -> Result<(), Error>;
+    fn settle_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        shares: u32,
+        agreement_id: u64,
+    ) -> bool;
+    fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool);
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
+    fn approve_shares(env: Env, owner: Address, spender: Address, token_id: u64, shares: u32);
+    fn share_allowance(env: Env, owner: Address, spender: Address, token_id: u64) -> u32;
     fn burn_shares(env: Env, owner: Address, token_id: u64, shares: u32) -> bool;
     fn is_sole_owner(env: Env, token_id: u64, address: Address) -> bool;
     fn merge_shares(env: Env, owner: Address, token_id: u64) -> u32;
-    fn grant_temporary_control(env: Env, token_id: u64, renter: Address, end_time: u64);
-    fn revoke_temporary_control(env: Env, token_id: u64, renter: Address);
+    fn grant_temporary_control(
+        env: Env,
+        token_id: u64,
+        renter: Address,
+        end_time: u64,
+        agreement_id: u64,
+    );
+    fn revoke_temporary_control(env: Env, token_id: u64, renter: Address, agreement_id: u64);
     fn has_control(env: Env, token_id: u64, address: Address) -> bool;
+    fn set_user(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        user: Address,
+        expires: u64,
+    ) -> Result<(), Error>;
+    fn user_of(env: Env, token_id: u64) -> Vec<Address>;
+    fn user_expires(env: Env, token_id: u64, user: Address) -> u64;
+    fn set_share_schedule(
+        env: Env,
+        token_id: u64,
+        beneficiary: Address,
+        schedule: ShareSchedule,
+    ) -> Result<(), Error>;
+    fn share_schedule(env: Env, token_id: u64, owner: Address) -> Option<ShareSchedule>;
+    fn vested_balance(env: Env, token_id: u64, owner: Address) -> u32;
+    fn locked_balance(env: Env, token_id: u64, owner: Address) -> u32;
+    fn snapshot(env: Env, token_id: u64) -> u32;
+    fn current_snapshot(env: Env, token_id: u64) -> u32;
+    fn balance_of_at(env: Env, token_id: u64, owner: Address, snapshot_id: u32)
+        -> Result<u32, Error>;
+    fn total_supply_at(env: Env, token_id: u64, snapshot_id: u32) -> Result<u32, Error>;
     fn balance_of(env: Env, token_id: u64, owner: Address) -> u32;
     fn total_supply(env: Env, token_id: u64) -> u32;
     fn get_all_owners(env: Env, token_id: u64) -> Option<Map<Address, u32>>;
     fn token_uri(env: Env, token_id: u64) -> String;
-    fn set_token_uri(env: Env, token_id: u64, uri: String);
+    fn set_token_uri(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        uri: String,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, Error>;
+    fn set_attributes(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        attributes: Map<Symbol, String>,
+    ) -> Result<u32, Error>;
     fn get_metadata(env: Env, token_id: u64) -> Option<TokenMetadata>;
+    fn metadata_version(env: Env, token_id: u64, version: u32) -> Option<TokenMetadata>;
     fn tokens_of_owner(env: Env, owner: Address) -> Vec<(u64, u32)>;
     fn exists(env: Env, token_id: u64) -> bool;
+    fn provenance(env: Env, token_id: u64, cursor: u32, limit: u32) -> ProvenancePage;
+    fn provenance_len(env: Env, token_id: u64) -> u32;
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
-use soroban_sdk::{contracterror, contracttype, symbol_short, Address, String, Symbol};
+use soroban_sdk::{
+    contracterror, contracttype, symbol_short, Address, BytesN, Map, String, Symbol, Vec,
+};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -13,7 +15,16 @@ pub enum Error {
     AgreementNotFound = 7,
     AgreementNotActive = 8,
     AgreementNotOwnedByCaller = 9,
-    StateNotAlreadySet = 11
+    StateNotAlreadySet = 11,
+    NotAuthorized = 12,
+    TooManyUsers = 13,
+    InsufficientAllowance = 14,
+    InvalidSnapshot = 15,
+    SharesLocked = 16,
+    InvalidSchedule = 17,
+    TooManyAttributes = 18,
+    UserBoundToAgreement = 19,
+    InsufficientShares = 20,
 }
 
 #[derive(Clone)]
@@ -22,8 +33,22 @@ pub enum DataKey {
     ContractName,                    // Name of the NFT collection
     ContractSymbol,                  // Symbol of the NFT collection
     TokenMetadata(u64),              // Metadata for each token ID
+    MetadataVersion(u64, u32),       // Metadata of a token as it was at an earlier version
     TokenOwnership(u64),             // Map of owners to their share amounts
     TemporaryControl(u64),           // Temporary control for rentals (renter, end_time)
+    TokenUsers(u64),                 // Map of users (renters, subscribers) to when their use expires
+    UserAgreement(u64, Address),     // Agreement the marketplace made a token user under
+    OperatorApproval(Address, Address), // Operator may move all of an owner's shares
+    ShareAllowance(u64, Address, Address), // Shares of a token a spender may move for an owner
+    OwnerTokens(Address),            // Token IDs an address holds shares in
+    TokensToMigrate,                 // Tokens minted before the upgrade, set by the first migrate
+    TokensMigrated,                  // Last token ID brought up to date by migrate
+    SnapshotCount(u64),              // Latest snapshot ID of a token
+    BalanceCheckpoints(u64, Address), // (snapshot ID, shares) an owner held at each snapshot
+    SupplyCheckpoints(u64),          // (snapshot ID, total shares) at each snapshot
+    ShareSchedule(u64, Address),     // Vesting or lockup on an owner's shares of a token
+    ProvenanceCount(u64),            // Number of provenance entries recorded for a token
+    Provenance(u64, u32),            // Provenance entry of a token, by position in its history
 }
 
 #[derive(Clone)]
@@ -34,11 +59,71 @@ pub struct Token {
     pub metadata: String,
 }
 
+#[derive(Clone)]
 #[contracttype]
 pub struct TokenMetadata {
     pub total_shares: u32,
-    pub token_uri: String
+    pub token_uri: String,
+    pub content_hash: BytesN<32>,         // Hash of the off-chain JSON at `token_uri`, zero at mint
+    pub attributes: Map<Symbol, String>,  // e.g. serial_number, condition, category
+    pub creator: Address,                 // May update the metadata, as may the marketplace
+    pub version: u32,                     // 1 at mint, bumped by every update
+    pub updated_at: u64,
+}
+
+// Metadata as stored by version 1, rewritten to `TokenMetadata` by `migrate`
+#[derive(Clone)]
+#[contracttype]
+pub struct TokenMetadataV1 {
+    pub total_shares: u32,
+    pub token_uri: String,
+}
+
+// Release of `shares` held by a beneficiary: nothing before `cliff`, then linear from `start`
+// until everything is released at `end`. A lockup is a schedule with `start`, `cliff` and `end`
+// all set to the unlock time.
+#[derive(Clone)]
+#[contracttype]
+pub struct ShareSchedule {
+    pub shares: u32,
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProvenanceKind {
+    Mint = 1,
+    Transfer = 2,       // The whole token changed hands
+    TransferShares = 3,
+    Burn = 4,
+    RentalStarted = 5,  // Temporary control granted or extended
+    RentalEnded = 6,
+    MetadataUpdated = 7,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ProvenanceEntry {
+    pub kind: ProvenanceKind,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub shares: u32,
+    pub agreement_id: u64,         // 0 when the change isn't tied to an agreement
+    pub token_uri: Option<String>, // New URI of a metadata update
+    pub timestamp: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ProvenancePage {
+    pub entries: Vec<ProvenanceEntry>,
+    pub next_cursor: Option<u32>, // Pass back as `cursor` to get the next page, `None` at the end
 }
 
 pub const ADMIN: Symbol = symbol_short!("ADMIN");
 pub const MARKETPLACE_CONTRACT: Symbol = symbol_short!("MAR_CA");
+pub const MAX_TOKEN_USERS: u32 = 20;
+pub const MAX_PAGE_SIZE: u32 = 50;
+pub const MAX_TOKEN_ATTRIBUTES: u32 = 32;
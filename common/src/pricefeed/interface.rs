@@ -1,6 +1,6 @@
-use soroban_sdk::{contractclient, Address, BytesN, Env, Symbol};
+use soroban_sdk::{contractclient, Address, BytesN, Env, Symbol, Vec};
 
-use super::types::OracleError;
+use super::types::{CircuitBreaker, OracleError, ReportOutcome, SignedPriceReport};
 
 #[contractclient(name = "PriceOracleContractClient")]
 pub trait PriceOracleContractTrait {
@@ -13,17 +13,38 @@ pub trait PriceOracleContractTrait {
         max_price_change: i128,
     ) -> Result<(), OracleError>;
     fn version() -> u32;
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), OracleError>;
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), OracleError>;
     fn update_price(env: Env, updater: Address, new_rate: i128) -> Result<(), OracleError>;
     fn get_price(env: Env) -> Result<(i128, u64), OracleError>;
-    fn add_updater(env: Env, admin: Address, new_updater: Address) -> Result<(), OracleError>;
-    fn remove_updater(env: Env, admin: Address, updater: Address) -> Result<(), OracleError>;
+    fn add_updater(env: Env, caller: Address, new_updater: Address) -> Result<(), OracleError>;
+    fn remove_updater(env: Env, caller: Address, updater: Address) -> Result<(), OracleError>;
     fn update_config(
         env: Env,
-        admin: Address,
+        caller: Address,
         min_update_interval: u64,
         max_price_change: i128,
         valid_period: u64,
     ) -> Result<(), OracleError>;
+    fn trip_circuit_breaker(env: Env, caller: Address) -> Result<(), OracleError>;
+    fn reset_circuit_breaker(env: Env, caller: Address) -> Result<(), OracleError>;
+    fn force_set_price(env: Env, caller: Address, new_rate: i128) -> Result<(), OracleError>;
+    fn get_circuit_breaker(env: Env) -> CircuitBreaker;
+    fn is_circuit_breaker_tripped(env: Env) -> bool;
+    fn add_reporter(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), OracleError>;
+    fn remove_reporter(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), OracleError>;
+    fn get_reporters(env: Env) -> Vec<BytesN<32>>;
+    fn set_min_reports(env: Env, caller: Address, min_reports: u32) -> Result<(), OracleError>;
+    fn get_report_nonce(env: Env) -> u64;
+    fn submit_reports(
+        env: Env,
+        timestamp: u64,
+        nonce: u64,
+        reports: Vec<SignedPriceReport>,
+    ) -> Result<ReportOutcome, OracleError>;
 }
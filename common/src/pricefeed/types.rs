@@ -1,4 +1,6 @@
-use soroban_sdk::{contracterror, contracttype, Address, Vec};
+use soroban_sdk::{
+    contracterror, contracttype, crypto::Hash, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec,
+};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -11,7 +13,13 @@ pub enum OracleError {
     MaximumUpdateInterval = 5,
     StalePrice = 6,
     NotInitialized = 7,
-    StateNotAlreadySet = 8
+    StateNotAlreadySet = 8,
+    CircuitBreakerTripped = 9,
+    UnknownReporter = 10,
+    DuplicateReport = 11,
+    InsufficientReports = 12,
+    InvalidNonce = 13,
+    InvalidTimestamp = 14,
 }
 
 #[derive(Clone)]
@@ -29,4 +37,51 @@ pub struct OracleConfig {
     pub updaters: Vec<Address>,
     pub min_update_interval: u64,
     pub max_price_change: i128,
-}
\ No newline at end of file
+}
+// Set when an update exceeds `max_price_change` (or by the admin) and cleared by the admin
+// once the price has been reviewed. While tripped the feed serves no price.
+#[derive(Clone)]
+#[contracttype]
+pub struct CircuitBreaker {
+    pub tripped: bool,
+    pub tripped_at: u64,
+    pub rejected_rate: i128, // Rate that tripped the breaker, 0 for a manual trip
+}
+
+// What became of the median rate of a relayed bundle. A rejected rate tripped the circuit
+// breaker, the bundle is still consumed so its nonce can't be replayed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ReportOutcome {
+    Accepted(i128),
+    Rejected(i128),
+}
+
+// Price observation signed off-chain by a registered reporter key. Reports are relayed in
+// bundles that share a timestamp and nonce, see `SignedPriceReport::hash` for the payload.
+#[derive(Clone)]
+#[contracttype]
+pub struct SignedPriceReport {
+    pub public_key: BytesN<32>,
+    pub rate: i128,
+    pub signature: BytesN<64>, // Ed25519 signature over `hash`
+}
+
+impl SignedPriceReport {
+    // feed address || timestamp (le) || nonce (le) || rate (le)
+    pub fn to_bytes(&self, env: &Env, feed: &Address, timestamp: u64, nonce: u64) -> Bytes {
+        let mut data: Bytes = Bytes::new(env);
+
+        data.append(&feed.clone().to_xdr(env));
+        data.extend_from_slice(&timestamp.to_le_bytes());
+        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(&self.rate.to_le_bytes());
+
+        data
+    }
+
+    pub fn hash(&self, env: &Env, feed: &Address, timestamp: u64, nonce: u64) -> Hash<32> {
+        env.crypto()
+            .sha256(&self.to_bytes(env, feed, timestamp, nonce))
+    }
+}
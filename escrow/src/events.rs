@@ -6,6 +6,7 @@ pub enum EscrowEvent {
     FundsLocked(u64, Address, Address, Address, i128),
     FundsReleased(u64, Address, i128),
     Refunded(u64, Address, i128),
+    FundsAdjusted(u64, i128, i128),
 }
 
 impl EscrowEvent {
@@ -16,6 +17,7 @@ impl EscrowEvent {
             EscrowEvent::FundsLocked(..) => stringify!(FundsLocked),
             EscrowEvent::FundsReleased(..) => stringify!(FundsReleased),
             EscrowEvent::Refunded(..) => stringify!(Refunded),
+            EscrowEvent::FundsAdjusted(..) => stringify!(FundsAdjusted),
         }
     }
 
@@ -44,6 +46,11 @@ impl EscrowEvent {
                 v.push_back(buyer.into_val(env));
                 v.push_back(amount.into_val(env));
             }
+            EscrowEvent::FundsAdjusted(listing_id, delta, amount) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(delta.into_val(env));
+                v.push_back(amount.into_val(env));
+            }
         }
 
         env.events().publish((self.name(),), v)
@@ -1,6 +1,11 @@
 #![no_std]
 
 mod events;
+use common::impl_access_control;
+use common::access::{
+    roles::{init_admin, require_role},
+    types::Role,
+};
 use common::escrow::{
     interface::EscrowContractTrait,
     types::{Escrow, EscrowDataKey as DataKey, EscrowError as Error, EscrowStatus},
@@ -14,6 +19,10 @@ pub const ADMIN: Symbol = symbol_short!("ADMIN");
 #[contract]
 pub struct EscrowContract;
 
+impl_access_control!(EscrowContract, |env: &Env| -> Address {
+    env.storage().instance().get(&ADMIN).unwrap()
+});
+
 #[contractimpl]
 impl EscrowContractTrait for EscrowContract {
     // Initialize escrow
@@ -28,6 +37,7 @@ impl EscrowContractTrait for EscrowContract {
             .instance()
             .set(&MARKETPLACE_CONTRACT, &marketplace_contract_id);
         env.storage().instance().set(&ADMIN, &admin);
+        init_admin(&env, &admin);
         EscrowEvent::Initialized.publish(&env);
         Ok(())
     }
@@ -36,16 +46,19 @@ impl EscrowContractTrait for EscrowContract {
         2
     }
 
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
         EscrowEvent::Upgraded(Self::version()).publish(&env);
     }
 
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error> {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, Role::Admin, &caller);
 
         if !env.storage().instance().has::<Symbol>(&state_key) {
             return Err(Error::StateNotAlreadySet);
@@ -159,6 +172,36 @@ impl EscrowContractTrait for EscrowContract {
         Ok(())
     }
 
+    // Change the amount held for an active escrow, e.g. after an agreement amendment.
+    // An increase must already have been transferred in by the marketplace; a decrease
+    // is refunded to the buyer. Returns the new amount.
+    fn adjust_amount(env: Env, listing_id: u64, delta: i128) -> Result<i128, Error> {
+        require_marketplace(&env);
+        let mut escrow: Escrow = Self::get_escrow(env.clone(), listing_id)?;
+
+        if !matches!(escrow.status, EscrowStatus::Active) {
+            return Err(Error::EscrowNotActive);
+        }
+
+        if escrow.amount + delta < 0 {
+            return Err(Error::InsufficientFunds);
+        }
+
+        if delta < 0 {
+            let token_client: token::TokenClient<'_> = token::Client::new(&env, &escrow.token);
+            token_client.transfer(&env.current_contract_address(), &escrow.buyer, &-delta);
+        }
+
+        escrow.amount += delta;
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(listing_id), &escrow);
+
+        EscrowEvent::FundsAdjusted(listing_id, delta, escrow.amount).publish(&env);
+
+        Ok(escrow.amount)
+    }
+
     // Implement a method for admin to withdraw escrow funds in case of emergencies 
 }
 
@@ -1,6 +1,6 @@
 use soroban_sdk::{Address, Env, IntoVal, String, Val, Vec};
 
-use crate::types::PurchaseType;
+use crate::types::{PriceSource, ProposalStatus, PurchaseType};
 
 pub enum MarketplaceEvent {
     Initialized(Address, Address, Address),
@@ -12,6 +12,30 @@ pub enum MarketplaceEvent {
     ConfirmedReceipt(u64, Address),
     SaleOrRentalCancelled(u64, Address),
     AssetReclaimed(u64, Address),
+    PriceSourcesUpdated(PriceSource, PriceSource, u64),
+    EmergencyPriceSet(i128, u64),
+    EmergencyPriceCleared(Address),
+    PriceQuoted(PriceSource, i128),
+    AmendmentSettled(u64, u64, u32, i128),
+    RenewalProcessed(u64, u64, bool, i128),
+    RentalExpired(u64, u64, Address),
+    GovernanceConfigUpdated(u32, u32, u64),
+    ProposalCreated(u64, u64, Address),
+    VoteCast(u64, Address, bool, u32),
+    ProposalFinalized(u64, ProposalStatus),
+    ReserveDeposited(u64, Address, i128),
+    DragAlongUpdated(u32),
+    BuyoutOffered(u64, u64, Address, i128),
+    BuyoutAccepted(u64, Address, u32),
+    BuyoutCompleted(u64, u64, Address),
+    BuyoutCancelled(u64),
+    ReservedSharesGranted(u64, Address, u32),
+    ListingDecommissioned(u64, i128),
+    SharesRedeemed(u64, Address, u32, i128),
+    RenterRequirementUpdated(u64, u32, u32),
+    VerifierUpdated(Address, bool),
+    VerificationUpdated(Address, u32, u64),
+    ListingKycLevelUpdated(u64, u32),
 }
 
 impl MarketplaceEvent {
@@ -26,6 +50,30 @@ impl MarketplaceEvent {
             MarketplaceEvent::ConfirmedReceipt(..) => stringify!(ConfirmedReceipt),
             MarketplaceEvent::SaleOrRentalCancelled(..) => stringify!(SaleOrRentalCancelled),
             MarketplaceEvent::AssetReclaimed(..) => stringify!(AssetReclaimed),
+            MarketplaceEvent::PriceSourcesUpdated(..) => stringify!(PriceSourcesUpdated),
+            MarketplaceEvent::EmergencyPriceSet(..) => stringify!(EmergencyPriceSet),
+            MarketplaceEvent::EmergencyPriceCleared(..) => stringify!(EmergencyPriceCleared),
+            MarketplaceEvent::PriceQuoted(..) => stringify!(PriceQuoted),
+            MarketplaceEvent::AmendmentSettled(..) => stringify!(AmendmentSettled),
+            MarketplaceEvent::RenewalProcessed(..) => stringify!(RenewalProcessed),
+            MarketplaceEvent::RentalExpired(..) => stringify!(RentalExpired),
+            MarketplaceEvent::GovernanceConfigUpdated(..) => stringify!(GovernanceConfigUpdated),
+            MarketplaceEvent::ProposalCreated(..) => stringify!(ProposalCreated),
+            MarketplaceEvent::VoteCast(..) => stringify!(VoteCast),
+            MarketplaceEvent::ProposalFinalized(..) => stringify!(ProposalFinalized),
+            MarketplaceEvent::ReserveDeposited(..) => stringify!(ReserveDeposited),
+            MarketplaceEvent::DragAlongUpdated(..) => stringify!(DragAlongUpdated),
+            MarketplaceEvent::BuyoutOffered(..) => stringify!(BuyoutOffered),
+            MarketplaceEvent::BuyoutAccepted(..) => stringify!(BuyoutAccepted),
+            MarketplaceEvent::BuyoutCompleted(..) => stringify!(BuyoutCompleted),
+            MarketplaceEvent::BuyoutCancelled(..) => stringify!(BuyoutCancelled),
+            MarketplaceEvent::ReservedSharesGranted(..) => stringify!(ReservedSharesGranted),
+            MarketplaceEvent::ListingDecommissioned(..) => stringify!(ListingDecommissioned),
+            MarketplaceEvent::SharesRedeemed(..) => stringify!(SharesRedeemed),
+            MarketplaceEvent::RenterRequirementUpdated(..) => stringify!(RenterRequirementUpdated),
+            MarketplaceEvent::VerifierUpdated(..) => stringify!(VerifierUpdated),
+            MarketplaceEvent::VerificationUpdated(..) => stringify!(VerificationUpdated),
+            MarketplaceEvent::ListingKycLevelUpdated(..) => stringify!(ListingKycLevelUpdated),
         }
     }
 
@@ -72,6 +120,119 @@ impl MarketplaceEvent {
                 v.push_back(listing_id.into_val(env));
                 v.push_back(owner.into_val(env));
             }
+            MarketplaceEvent::PriceSourcesUpdated(primary, secondary, max_age) => {
+                v.push_back(primary.into_val(env));
+                v.push_back(secondary.into_val(env));
+                v.push_back(max_age.into_val(env));
+            }
+            MarketplaceEvent::EmergencyPriceSet(rate, expires_at) => {
+                v.push_back(rate.into_val(env));
+                v.push_back(expires_at.into_val(env));
+            }
+            MarketplaceEvent::EmergencyPriceCleared(caller) => {
+                v.push_back(caller.into_val(env));
+            }
+            MarketplaceEvent::PriceQuoted(source, rate) => {
+                v.push_back(source.into_val(env));
+                v.push_back(rate.into_val(env));
+            }
+            MarketplaceEvent::AmendmentSettled(listing_id, agreement_id, version, amount) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(version.into_val(env));
+                v.push_back(amount.into_val(env));
+            }
+            MarketplaceEvent::RenewalProcessed(listing_id, agreement_id, paid, amount) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(paid.into_val(env));
+                v.push_back(amount.into_val(env));
+            }
+            MarketplaceEvent::RentalExpired(listing_id, agreement_id, renter) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(agreement_id.into_val(env));
+                v.push_back(renter.into_val(env));
+            }
+            MarketplaceEvent::GovernanceConfigUpdated(quorum_bps, approval_bps, voting_period) => {
+                v.push_back(quorum_bps.into_val(env));
+                v.push_back(approval_bps.into_val(env));
+                v.push_back(voting_period.into_val(env));
+            }
+            MarketplaceEvent::ProposalCreated(proposal_id, listing_id, proposer) => {
+                v.push_back(proposal_id.into_val(env));
+                v.push_back(listing_id.into_val(env));
+                v.push_back(proposer.into_val(env));
+            }
+            MarketplaceEvent::VoteCast(proposal_id, voter, support, weight) => {
+                v.push_back(proposal_id.into_val(env));
+                v.push_back(voter.into_val(env));
+                v.push_back(support.into_val(env));
+                v.push_back(weight.into_val(env));
+            }
+            MarketplaceEvent::ProposalFinalized(proposal_id, status) => {
+                v.push_back(proposal_id.into_val(env));
+                v.push_back(status.into_val(env));
+            }
+            MarketplaceEvent::ReserveDeposited(listing_id, from, amount) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(from.into_val(env));
+                v.push_back(amount.into_val(env));
+            }
+            MarketplaceEvent::DragAlongUpdated(threshold_bps) => {
+                v.push_back(threshold_bps.into_val(env));
+            }
+            MarketplaceEvent::BuyoutOffered(offer_id, listing_id, buyer, amount) => {
+                v.push_back(offer_id.into_val(env));
+                v.push_back(listing_id.into_val(env));
+                v.push_back(buyer.into_val(env));
+                v.push_back(amount.into_val(env));
+            }
+            MarketplaceEvent::BuyoutAccepted(offer_id, holder, shares) => {
+                v.push_back(offer_id.into_val(env));
+                v.push_back(holder.into_val(env));
+                v.push_back(shares.into_val(env));
+            }
+            MarketplaceEvent::BuyoutCompleted(offer_id, listing_id, buyer) => {
+                v.push_back(offer_id.into_val(env));
+                v.push_back(listing_id.into_val(env));
+                v.push_back(buyer.into_val(env));
+            }
+            MarketplaceEvent::BuyoutCancelled(offer_id) => {
+                v.push_back(offer_id.into_val(env));
+            }
+            MarketplaceEvent::ReservedSharesGranted(listing_id, beneficiary, shares) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(beneficiary.into_val(env));
+                v.push_back(shares.into_val(env));
+            }
+            MarketplaceEvent::ListingDecommissioned(listing_id, proceeds) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(proceeds.into_val(env));
+            }
+            MarketplaceEvent::SharesRedeemed(listing_id, holder, shares, payout) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(holder.into_val(env));
+                v.push_back(shares.into_val(env));
+                v.push_back(payout.into_val(env));
+            }
+            MarketplaceEvent::RenterRequirementUpdated(listing_id, min_average, min_reviews) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(min_average.into_val(env));
+                v.push_back(min_reviews.into_val(env));
+            }
+            MarketplaceEvent::VerifierUpdated(verifier, enabled) => {
+                v.push_back(verifier.into_val(env));
+                v.push_back(enabled.into_val(env));
+            }
+            MarketplaceEvent::VerificationUpdated(address, level, expires_at) => {
+                v.push_back(address.into_val(env));
+                v.push_back(level.into_val(env));
+                v.push_back(expires_at.into_val(env));
+            }
+            MarketplaceEvent::ListingKycLevelUpdated(listing_id, level) => {
+                v.push_back(listing_id.into_val(env));
+                v.push_back(level.into_val(env));
+            }
         }
 
         env.events().publish((self.name(),), v)
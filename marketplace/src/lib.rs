@@ -7,29 +7,68 @@ mod storage;
 mod types;
 mod utils;
 
+use core::cmp::Ordering;
+
+use common::{
+    access::{
+        roles::{init_admin, require_role},
+        types::Role,
+    },
+    agreement::types::{
+        Agreement, AgreementStatus, AgreementType, Amendment, Subscription, MAX_RATING,
+    },
+    impl_access_control,
+    nft::types::ShareSchedule,
+};
 use events::MarketplaceEvent;
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, Address, BytesN, Env, Map, String, Symbol, Vec,
+    contract, contractimpl, panic_with_error, token, Address, BytesN, Env, Map, String, Symbol,
+    Vec,
 };
 use storage::{
-    get_data, get_persistent, has_data, remove_persistent, store_data, store_persistent,
+    get_data, get_persistent, has_data, has_persistent, remove_data, remove_persistent,
+    store_data, store_persistent,
 };
 use types::{
-    Currency, DataKey, Error, Listing, ListingStatus, PurchaseType, ADMIN, AGREEMENT_CONTRACT,
-    CURRENCY, ESCROW_CONTRACT, NFT_CONTRACT, PAYMENT_TOKEN, PRICE_FEED_CONTRACT, REFLECTOR_ORACLE,
+    BuyoutOffer, BuyoutStatus, Currency, DataKey, EmergencyPrice, Error, GovernanceConfig,
+    Identity, Listing, ListingStatus, PriceSource, PriceSourceConfig, Proposal, ProposalAction,
+    ProposalStatus, PurchaseType, Quote, ReputationRequirement, ADMIN, AGREEMENT_CONTRACT,
+    CURRENCY, DRAG_ALONG, EMERGENCY_PRICE, ESCROW_CONTRACT, GOVERNANCE, LISTING_KYC,
+    NFT_CONTRACT, PAYMENT_TOKEN, PRICE_FEED_CONTRACT, PRICE_SOURCES, REFLECTOR_ORACLE,
 };
 use utils::{
+    buyout::{
+        cancel_buyout, complete_buyout, get_buyout, get_drag_along_bps, is_drag_along_reached,
+        store_buyout,
+    },
     contract_clients::{get_agreement_client, get_escrow_client, get_nft_client},
+    governance::{
+        execute_action, get_governance_config, has_passed, is_decided, is_valid_config,
+        next_proposal_id, store_proposal,
+    },
     helpers::{
-        complete_agreement, create_purchase_agreement, distribute_dividends, get_listing_by_id,
-        get_usdc_price, parse_amount, remove_listing, terminate_agreement,
-        transfer_and_lock_tokens, transfer_tokens_to_owner,
+        collect_from_allowance, complete_agreement, create_purchase_agreement,
+        distribute_dividends, get_listing_by_id, get_share_holders, get_usdc_price,
+        convert_amount, is_agreement_escrow, is_co_owned, meets_renter_requirement, parse_amount,
+        pay_shareholders, remove_listing, require_accepted_terms, sync_rental_control,
+        terminate_agreement,
+        top_up_escrow, transfer_and_lock_tokens, transfer_tokens_to_owner,
     },
+    identity::{
+        get_identity, get_listing_creation_level, get_listing_kyc_level, is_verifier,
+        require_verified, verification_level,
+    },
+    price_source::{get_price_source_config, get_quote},
+    redemption::{decommission_listing, get_redemption_pool, redeem_shares},
 };
 
 #[contract]
 pub struct MarketplaceContract;
 
+impl_access_control!(MarketplaceContract, |env: &Env| -> Address {
+    get_data(env, &ADMIN).unwrap()
+});
+
 #[allow(dead_code)]
 #[contractimpl]
 impl MarketplaceContract {
@@ -56,6 +95,7 @@ impl MarketplaceContract {
         store_data(&env, &REFLECTOR_ORACLE, &reflector_ca);
         store_data(&env, &PAYMENT_TOKEN, &payment_token);
         store_data(&env, &CURRENCY, &Currency::NGNG);
+        init_admin(&env, &admin);
 
         MarketplaceEvent::Initialized(nft_ca, agreement_ca, escrow_ca).publish(&env);
         Ok(())
@@ -65,16 +105,14 @@ impl MarketplaceContract {
         5
     }
 
-    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
         MarketplaceEvent::Upgraded(Self::version()).publish(&env);
     }
 
-    pub fn update_state(env: Env, state_key: Symbol, state_value: Address) {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    pub fn update_state(env: Env, caller: Address, state_key: Symbol, state_value: Address) {
+        require_role(&env, Role::Admin, &caller);
 
         if !env.storage().instance().has::<Symbol>(&state_key) {
             panic_with_error!(&env, Error::StateNotAlreadySet);
@@ -85,15 +123,13 @@ impl MarketplaceContract {
             .publish(("state_updated", state_key), state_value);
     }
 
-    pub fn set_payment_token(env: Env, token_addr: Address) {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    pub fn set_payment_token(env: Env, caller: Address, token_addr: Address) {
+        require_role(&env, Role::Admin, &caller);
         store_data(&env, &PAYMENT_TOKEN, &token_addr);
     }
 
-    pub fn set_currency(env: Env, currency: Currency) {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    pub fn set_currency(env: Env, caller: Address, currency: Currency) {
+        require_role(&env, Role::Admin, &caller);
 
         if currency == Currency::XLM {
             panic_with_error!(&env, Error::CurrencyNotSupported);
@@ -101,6 +137,71 @@ impl MarketplaceContract {
         store_data(&env, &CURRENCY, &currency);
     }
 
+    // Order in which price sources are tried before falling back to the emergency price
+    pub fn set_price_sources(
+        env: Env,
+        caller: Address,
+        primary: PriceSource,
+        secondary: PriceSource,
+        max_age: u64,
+    ) {
+        require_role(&env, Role::OracleManager, &caller);
+
+        // The emergency price is always the last resort, so it can't also be a regular source
+        if primary == secondary
+            || primary == PriceSource::Emergency
+            || secondary == PriceSource::Emergency
+        {
+            panic_with_error!(&env, Error::InvalidPriceSources);
+        }
+
+        let config: PriceSourceConfig = PriceSourceConfig {
+            primary,
+            secondary,
+            max_age,
+        };
+        store_data(&env, &PRICE_SOURCES, &config);
+
+        MarketplaceEvent::PriceSourcesUpdated(primary, secondary, max_age).publish(&env);
+    }
+
+    pub fn get_price_sources(env: Env) -> PriceSourceConfig {
+        get_price_source_config(&env)
+    }
+
+    // Last resort rate for when no source has a fresh price, ignored once `expires_at` passes
+    pub fn set_emergency_price(env: Env, caller: Address, rate: i128, expires_at: u64) {
+        require_role(&env, Role::OracleManager, &caller);
+
+        if rate <= 0 {
+            panic_with_error!(&env, Error::InvalidPrice);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic_with_error!(&env, Error::InvalidEmergencyPriceExpiry);
+        }
+
+        let price: EmergencyPrice = EmergencyPrice {
+            rate,
+            timestamp: env.ledger().timestamp(),
+            expires_at,
+        };
+        store_data(&env, &EMERGENCY_PRICE, &price);
+
+        MarketplaceEvent::EmergencyPriceSet(rate, expires_at).publish(&env);
+    }
+
+    pub fn clear_emergency_price(env: Env, caller: Address) {
+        require_role(&env, Role::OracleManager, &caller);
+        remove_data(&env, &EMERGENCY_PRICE);
+
+        MarketplaceEvent::EmergencyPriceCleared(caller).publish(&env);
+    }
+
+    // Current quote currency / USD rate and the source it came from
+    pub fn get_price_quote(env: Env) -> Quote {
+        get_quote(&env)
+    }
+
     pub fn create_listing(
         env: Env,
         creator: Address,
@@ -112,8 +213,10 @@ impl MarketplaceContract {
         allow_rent: bool,
         total_shares: u32,
         reserved_shares: u32,
+        kyc_level: u32,
     ) -> Result<u64, Error> {
         creator.require_auth();
+        require_verified(&env, &creator, get_listing_creation_level(&env));
 
         // Ensure reserved shares don't exceed total shares
         if reserved_shares > total_shares {
@@ -142,13 +245,11 @@ impl MarketplaceContract {
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
         store_data(&env, &DataKey::ListingCount, &listing_id);
 
-        let mut ownership_shares: Map<Address, u32> = Map::new(&env);
-        ownership_shares.set(creator.clone(), total_shares); // Initial creator owns 100%
-        store_persistent(
-            &env,
-            &DataKey::OwnershipShares(listing_id),
-            &ownership_shares,
-        );
+        // Renters and buyers are gated from the start, there's no window without the level
+        if kyc_level > 0 {
+            store_persistent(&env, &DataKey::ListingKycLevel(listing_id), &kyc_level);
+            MarketplaceEvent::ListingKycLevelUpdated(listing_id, kyc_level).publish(&env);
+        }
 
         // Add to user's listings
         let mut user_listings: Vec<u64> =
@@ -198,19 +299,11 @@ impl MarketplaceContract {
 
         // Add the shares
         listing.total_shares = shares_to_add;
+        listing.reserved_shares = reserved_shares;
         listing.available_shares = shares_to_add - reserved_shares;
 
-        // Update ownership shares
-        let mut ownership_shares: Map<Address, u32> = Map::new(&env);
-        ownership_shares.set(creator.clone(), shares_to_add);
-
         // Update storage
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
-        store_persistent(
-            &env,
-            &DataKey::OwnershipShares(listing_id),
-            &ownership_shares,
-        );
 
         // Mint NFT shares to the creator
         get_nft_client(&env).mint(&creator, &listing_id, &shares_to_add, &listing.metadata_uri);
@@ -228,6 +321,10 @@ impl MarketplaceContract {
     ) {
         let mut listing: Listing = get_listing_by_id(&env, listing_id);
         listing.creator.require_auth();
+        // Co-owned listings change through shareholder proposals
+        if is_co_owned(&env, listing_id) {
+            panic_with_error!(&env, Error::ListingCoOwned);
+        }
         listing.reference_id = reference_id;
         listing.duration = new_duration;
         listing.allow_purchase = allow_purchase;
@@ -259,28 +356,152 @@ impl MarketplaceContract {
         get_data(&env, &DataKey::ListingCount).unwrap_or(0)
     }
 
-    // Remove
-    pub fn update_listing_count(env: Env, count: u64) {
+    // Admin repairs for listings left in a bad state
+    pub fn update_listing_count(env: Env, caller: Address, count: u64) {
+        require_role(&env, Role::Admin, &caller);
         store_data(&env, &DataKey::ListingCount, &count);
     }
 
-    pub fn remove_listing(env: Env, listing_id: u64) {
+    pub fn remove_listing(env: Env, caller: Address, listing_id: u64) {
+        require_role(&env, Role::Admin, &caller);
         remove_persistent(&env, &DataKey::Listing(listing_id));
     }
 
-    pub fn change_listing_status(env: Env, listing_id: u64, status: ListingStatus) {
+    pub fn change_listing_status(
+        env: Env,
+        caller: Address,
+        listing_id: u64,
+        status: ListingStatus,
+    ) {
+        require_role(&env, Role::Admin, &caller);
         let mut listing: Listing = get_listing_by_id(&env, listing_id);
         listing.status = status;
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
     }
 
+    // Only let renters and subscribers with at least `min_reviews` reviews averaging
+    // `min_average` hundredths of a star or more take the listing, e.g. for high-value gear.
+    // Zero for both lifts the requirement.
+    pub fn set_renter_requirement(
+        env: Env,
+        listing_id: u64,
+        min_average: u32,
+        min_reviews: u32,
+    ) -> Result<(), Error> {
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        listing.creator.require_auth();
+
+        if min_average > MAX_RATING * 100 {
+            return Err(Error::InvalidReputationRequirement);
+        }
+
+        if min_average == 0 && min_reviews == 0 {
+            remove_persistent(&env, &DataKey::RenterRequirement(listing_id));
+        } else {
+            let requirement = ReputationRequirement {
+                min_average,
+                min_reviews,
+            };
+            store_persistent(&env, &DataKey::RenterRequirement(listing_id), &requirement);
+        }
+
+        MarketplaceEvent::RenterRequirementUpdated(listing_id, min_average, min_reviews)
+            .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_renter_requirement(env: Env, listing_id: u64) -> Option<ReputationRequirement> {
+        get_persistent(&env, &DataKey::RenterRequirement(listing_id))
+    }
+
+    // Appoint or dismiss an identity verifier
+    pub fn set_verifier(env: Env, caller: Address, verifier: Address, enabled: bool) {
+        require_role(&env, Role::Admin, &caller);
+
+        if enabled {
+            store_persistent(&env, &DataKey::Verifier(verifier.clone()), &true);
+        } else {
+            remove_persistent(&env, &DataKey::Verifier(verifier.clone()));
+        }
+
+        MarketplaceEvent::VerifierUpdated(verifier, enabled).publish(&env);
+    }
+
+    pub fn is_verifier(env: Env, address: Address) -> bool {
+        is_verifier(&env, &address)
+    }
+
+    // A verifier records the level `address` was verified to, valid until `expires_at`.
+    // Level 0 revokes the verification.
+    pub fn set_verification(
+        env: Env,
+        verifier: Address,
+        address: Address,
+        level: u32,
+        expires_at: u64,
+    ) -> Result<(), Error> {
+        verifier.require_auth();
+        if !is_verifier(&env, &verifier) {
+            return Err(Error::NotAVerifier);
+        }
+
+        if level == 0 {
+            remove_persistent(&env, &DataKey::Identity(address.clone()));
+        } else {
+            if expires_at <= env.ledger().timestamp() {
+                return Err(Error::InvalidVerificationExpiry);
+            }
+            let identity = Identity {
+                level,
+                expires_at,
+                verifier,
+            };
+            store_persistent(&env, &DataKey::Identity(address.clone()), &identity);
+        }
+
+        MarketplaceEvent::VerificationUpdated(address, level, expires_at).publish(&env);
+        Ok(())
+    }
+
+    pub fn get_verification(env: Env, address: Address) -> Option<Identity> {
+        get_identity(&env, &address)
+    }
+
+    // Level `address` is verified to now, 0 if unverified or expired
+    pub fn get_verification_level(env: Env, address: Address) -> u32 {
+        verification_level(&env, &address)
+    }
+
+    // Verification level needed to create listings, 0 for none
+    pub fn set_listing_creation_level(env: Env, caller: Address, level: u32) {
+        require_role(&env, Role::Admin, &caller);
+        store_data(&env, &LISTING_KYC, &level);
+    }
+
+    pub fn get_listing_creation_level(env: Env) -> u32 {
+        get_listing_creation_level(&env)
+    }
+
+    // Change the verification level renters and buyers of the listing need, 0 for none
+    pub fn set_listing_kyc_level(env: Env, listing_id: u64, level: u32) {
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        listing.creator.require_auth();
+
+        store_persistent(&env, &DataKey::ListingKycLevel(listing_id), &level);
+        MarketplaceEvent::ListingKycLevelUpdated(listing_id, level).publish(&env);
+    }
+
+    pub fn get_listing_kyc_level(env: Env, listing_id: u64) -> u32 {
+        get_listing_kyc_level(&env, listing_id)
+    }
+
     pub fn get_listing_current_price(env: Env, listing_id: u64) -> i128 {
         let listing: Listing = get_listing_by_id(&env, listing_id);
-        parse_amount(&env, &listing.price)
+        convert_amount(&env, &listing.price).0
     }
 
     pub fn get_usdc_amount(env: Env, amount: i128) -> i128 {
-        parse_amount(&env, &amount)
+        convert_amount(&env, &amount).0
     }
 
     pub fn get_usdc_price(env: Env) -> (i128, u32) {
@@ -294,6 +515,14 @@ impl MarketplaceContract {
         if listing.status != ListingStatus::Available {
             panic_with_error!(&env, Error::ListingNotAvailable);
         }
+        // The token changes hands when a buyout completes, a rental would be cut short
+        if has_persistent(&env, &DataKey::ListingBuyout(listing_id)) {
+            panic_with_error!(&env, Error::BuyoutPending);
+        }
+        if !meets_renter_requirement(&env, listing_id, &renter) {
+            panic_with_error!(&env, Error::ReputationTooLow);
+        }
+        require_verified(&env, &renter, get_listing_kyc_level(&env, listing_id));
 
         transfer_and_lock_tokens(
             &env,
@@ -329,6 +558,127 @@ impl MarketplaceContract {
         agreement_id
     }
 
+    // Recurring access to a rentable listing. The first period is paid now; later periods are
+    // pulled by `process_renewals` from an allowance the subscriber grants the marketplace.
+    pub fn subscribe(
+        env: Env,
+        listing_id: u64,
+        subscriber: Address,
+        period: u64,
+        grace_period: u64,
+    ) -> Result<u64, Error> {
+        subscriber.require_auth();
+        require_verified(&env, &subscriber, get_listing_kyc_level(&env, listing_id));
+
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        if listing.status != ListingStatus::Available || !listing.allow_rent {
+            return Err(Error::ListingNotAvailable);
+        }
+        if has_persistent(&env, &DataKey::ListingBuyout(listing_id)) {
+            return Err(Error::BuyoutPending);
+        }
+        // Each period costs the listing price, so it runs for the listing's duration
+        if period == 0 || period != listing.duration {
+            return Err(Error::InvalidSubscriptionPeriod);
+        }
+        if !meets_renter_requirement(&env, listing_id, &subscriber) {
+            return Err(Error::ReputationTooLow);
+        }
+
+        let token_amount: i128 = transfer_tokens_to_owner(
+            &env,
+            listing.price,
+            &subscriber,
+            &env.current_contract_address(),
+        );
+        distribute_dividends(&env, listing_id, token_amount)?;
+
+        let agreement_id: u64 = get_agreement_client(&env).create_subscription(
+            &listing_id,
+            &subscriber,
+            &listing.creator,
+            &period,
+            &listing.price,
+            &grace_period,
+        );
+
+        MarketplaceEvent::Purchase(
+            listing_id,
+            agreement_id,
+            PurchaseType::Subscribe,
+            listing.creator,
+            subscriber,
+        )
+        .publish(&env);
+
+        Ok(agreement_id)
+    }
+
+    // Keeper entry point, callable by anyone. Tries to collect the next period for up to
+    // `limit` due subscriptions and records the outcome on each agreement.
+    pub fn process_renewals(env: Env, limit: u32) -> Result<u32, Error> {
+        let agreement_client = get_agreement_client(&env);
+        let due: Vec<u64> = agreement_client.get_due_subscriptions(&limit);
+
+        for agreement_id in due.iter() {
+            let agreement: Agreement = agreement_client.get_agreement(&agreement_id);
+            let subscription: Subscription = agreement_client.get_subscription(&agreement_id);
+
+            // Past the grace period a payment would be refused, the subscription just ends
+            let lapsed: bool = agreement.end_time.is_some_and(|end_time| {
+                env.ledger().timestamp() > end_time + subscription.grace_period
+            });
+
+            let mut token_amount: i128 = 0;
+            if !subscription.cancelled && !lapsed {
+                if let Some(amount) =
+                    collect_from_allowance(&env, subscription.price, &agreement.user)
+                {
+                    distribute_dividends(&env, agreement.listing_id, amount)?;
+                    token_amount = amount;
+                }
+            }
+
+            let paid: bool = token_amount > 0;
+            agreement_client.record_renewal(&agreement_id, &paid);
+
+            MarketplaceEvent::RenewalProcessed(
+                agreement.listing_id,
+                agreement_id,
+                paid,
+                token_amount,
+            )
+            .publish(&env);
+        }
+
+        Ok(due.len())
+    }
+
+    // Keeper entry point, callable by anyone. For up to `limit` rentals past their end time the
+    // renter loses control of the token and the agreement is marked overdue until the owner
+    // confirms the return through `reclaim_or_return`.
+    pub fn process_expired(env: Env, limit: u32) -> u32 {
+        let agreement_client = get_agreement_client(&env);
+        let nft_client = get_nft_client(&env);
+        let expired: Vec<u64> = agreement_client.get_expired_leases(&limit);
+
+        for agreement_id in expired.iter() {
+            let agreement: Agreement = agreement_client.get_agreement(&agreement_id);
+
+            nft_client.revoke_temporary_control(
+                &agreement.listing_id,
+                &agreement.user,
+                &agreement_id,
+            );
+            agreement_client.mark_expired(&agreement_id);
+
+            MarketplaceEvent::RentalExpired(agreement.listing_id, agreement_id, agreement.user)
+                .publish(&env);
+        }
+
+        expired.len()
+    }
+
     pub fn purchase(env: Env, listing_id: u64, buyer: Address) -> u64 {
         buyer.require_auth();
         let mut listing = get_listing_by_id(&env, listing_id);
@@ -337,6 +687,18 @@ impl MarketplaceContract {
         if listing.status != ListingStatus::Available {
             panic_with_error!(&env, Error::ListingNotAvailable); // Can't transfer while rented
         }
+        if has_persistent(&env, &DataKey::ListingBuyout(listing_id)) {
+            panic_with_error!(&env, Error::BuyoutPending);
+        }
+
+        // Co-owners sell together, through `offer_buyout` or a passed `SellAsset` proposal
+        if is_co_owned(&env, listing_id)
+            && !has_persistent(&env, &DataKey::ApprovedSale(listing_id))
+        {
+            panic_with_error!(&env, Error::ListingCoOwned);
+        }
+        remove_persistent(&env, &DataKey::ApprovedSale(listing_id));
+        require_verified(&env, &buyer, get_listing_kyc_level(&env, listing_id));
 
         transfer_and_lock_tokens(
             &env,
@@ -346,23 +708,11 @@ impl MarketplaceContract {
             &buyer,
         );
 
-        // Update ownership shares in marketplace
-        let mut ownership_shares: Map<Address, u32> =
-            get_persistent(&env, &DataKey::OwnershipShares(listing_id)).unwrap_or(Map::new(&env));
-
-        for (owner, _) in ownership_shares.iter() {
-            // Remove all ownership & listings
-            ownership_shares.remove(owner.clone());
+        // The listing leaves its owners' indexes, the shares follow the token to the buyer
+        for (owner, _) in get_share_holders(&env, listing_id).iter() {
             remove_listing(&env, listing_id, owner);
         }
 
-        ownership_shares.set(buyer.clone(), listing.total_shares.clone());
-        store_persistent(
-            &env,
-            &DataKey::OwnershipShares(listing_id),
-            &ownership_shares,
-        );
-
         // Create a purchase agreement
         let agreement_id: u64 = create_purchase_agreement(
             &env,
@@ -388,35 +738,22 @@ impl MarketplaceContract {
         agreement_id
     }
 
-    pub fn purchase_and_confirm(env: Env, listing_id: u64, buyer: Address) -> u64 {
+    // Open a purchase agreement without escrow, on terms the buyer attaches and signs. Once
+    // the seller signed them too, `purchase_and_confirm` pays and settles it in one call.
+    pub fn request_purchase(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+        terms_hash: BytesN<32>,
+    ) -> u64 {
         buyer.require_auth();
         let mut listing = get_listing_by_id(&env, listing_id);
 
-        // Check if the NFT is currently rented
         if listing.status != ListingStatus::Available {
-            panic_with_error!(&env, Error::ListingNotAvailable); // Can't transfer while rented
-        }
-
-        transfer_tokens_to_owner(&env, listing.price.clone(), &buyer, &listing.creator);
-
-        // Update ownership shares in marketplace
-        let mut ownership_shares: Map<Address, u32> =
-            get_persistent(&env, &DataKey::OwnershipShares(listing_id)).unwrap_or(Map::new(&env));
-
-        for (owner, _) in ownership_shares.iter() {
-            // Remove all ownership & listings
-            ownership_shares.remove(owner.clone());
-            remove_listing(&env, listing_id, owner);
+            panic_with_error!(&env, Error::ListingNotAvailable);
         }
+        require_verified(&env, &buyer, get_listing_kyc_level(&env, listing_id));
 
-        ownership_shares.set(buyer.clone(), listing.total_shares.clone());
-        store_persistent(
-            &env,
-            &DataKey::OwnershipShares(listing_id),
-            &ownership_shares,
-        );
-
-        // Create a purchase agreement
         let agreement_id: u64 = create_purchase_agreement(
             &env,
             &listing_id.into(),
@@ -427,9 +764,72 @@ impl MarketplaceContract {
             &false,
         );
 
+        let agreement_client = get_agreement_client(&env);
+        agreement_client.attach_terms(&agreement_id, &buyer, &terms_hash, &1u32);
+        agreement_client.sign_terms(&agreement_id, &buyer, &terms_hash);
+
         listing.agreement_id = agreement_id;
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
 
+        agreement_id
+    }
+
+    // Pay for a requested purchase whose terms both parties signed: the shareholders are paid
+    // and the token goes straight to the buyer
+    pub fn purchase_and_confirm(env: Env, listing_id: u64, buyer: Address) -> u64 {
+        buyer.require_auth();
+        let mut listing = get_listing_by_id(&env, listing_id);
+
+        // Check if the NFT is currently rented
+        if listing.status != ListingStatus::Available {
+            panic_with_error!(&env, Error::ListingNotAvailable); // Can't transfer while rented
+        }
+        if has_persistent(&env, &DataKey::ListingBuyout(listing_id)) {
+            panic_with_error!(&env, Error::BuyoutPending);
+        }
+
+        // Co-owners sell together, through `offer_buyout` or a passed `SellAsset` proposal
+        if is_co_owned(&env, listing_id)
+            && !has_persistent(&env, &DataKey::ApprovedSale(listing_id))
+        {
+            panic_with_error!(&env, Error::ListingCoOwned);
+        }
+        require_verified(&env, &buyer, get_listing_kyc_level(&env, listing_id));
+
+        // Only the buyer's own requested purchase, once the seller accepted its terms
+        let agreement_id: u64 = listing.agreement_id;
+        let agreement_client = get_agreement_client(&env);
+        let agreement: Agreement = match agreement_client.try_get_agreement(&agreement_id) {
+            Ok(Ok(agreement)) => agreement,
+            _ => panic_with_error!(&env, Error::AgreementNotFound),
+        };
+        if agreement.user != buyer
+            || agreement.agreement_type != AgreementType::Purchase
+            || agreement.status != AgreementStatus::Created
+        {
+            panic_with_error!(&env, Error::AgreementNotOwnedByCaller);
+        }
+        require_accepted_terms(&env, agreement_id);
+        remove_persistent(&env, &DataKey::ApprovedSale(listing_id));
+
+        // Paid through the marketplace, which splits it between the shareholders
+        let marketplace: Address = env.current_contract_address();
+        let token_amount: i128 =
+            transfer_tokens_to_owner(&env, listing.price.clone(), &buyer, &marketplace);
+        let holders: Map<Address, u32> = get_share_holders(&env, listing_id);
+        pay_shareholders(&env, listing_id, &holders, token_amount);
+
+        // The listing leaves its owners' indexes, the shares follow the token to the buyer
+        for (owner, _) in holders.iter() {
+            remove_listing(&env, listing_id, owner);
+        }
+
+        get_nft_client(&env).transfer(&listing.creator, &buyer, &listing_id, &agreement_id);
+        agreement_client.complete_agreement(&agreement_id, &buyer);
+
+        listing.status = ListingStatus::Purchased;
+        store_persistent(&env, &DataKey::Listing(listing_id), &listing);
+
         MarketplaceEvent::Purchase(
             listing_id,
             agreement_id,
@@ -454,19 +854,17 @@ impl MarketplaceContract {
         let mut listing: Listing = get_persistent(&env, &DataKey::Listing(listing_id)).unwrap();
 
         // Validate purchase
-        if listing.total_shares < 1 {
+        if listing.total_shares < 1 || listing.status == ListingStatus::Decommissioned {
             panic_with_error!(&env, Error::ListingNotAvailable);
         }
 
         if shares_to_buy == 0 {
             panic_with_error!(&env, Error::InsufficientShares);
         }
+        require_verified(&env, &buyer, get_listing_kyc_level(&env, listing_id));
 
-        // Get current ownership shares
-        let mut ownership_shares: Map<Address, u32> =
-            get_persistent(&env, &DataKey::OwnershipShares(listing_id)).unwrap_or(Map::new(&env));
-
-        let seller_shares = ownership_shares.get(seller.clone()).unwrap_or(0);
+        // The NFT is the share register, holders may have moved shares on it directly
+        let seller_shares: u32 = get_nft_client(&env).balance_of(&listing_id, &seller);
         let available_shares = if listing.creator == seller {
             listing.available_shares
         } else {
@@ -477,22 +875,17 @@ impl MarketplaceContract {
             panic_with_error!(&env, Error::InsufficientSharesForPurchase);
         }
 
+        // Unvested or locked up shares can't be sold
+        if get_nft_client(&env).vested_balance(&listing_id, &seller) < shares_to_buy {
+            panic_with_error!(&env, Error::SharesLocked);
+        }
+
         // Calculate price for shares
         let share_price: i128 =
             (listing.price * shares_to_buy as i128) / listing.total_shares as i128;
         // process payment
         transfer_tokens_to_owner(&env, share_price, &buyer, &seller);
 
-        // Update ownership shares
-        let buyer_current_shares = ownership_shares.get(buyer.clone()).unwrap_or(0);
-        ownership_shares.set(buyer.clone(), buyer_current_shares + shares_to_buy);
-        ownership_shares.set(seller.clone(), seller_shares - shares_to_buy);
-        store_persistent(
-            &env,
-            &DataKey::OwnershipShares(listing_id),
-            &ownership_shares,
-        );
-
         if seller == listing.creator {
             listing.available_shares -= shares_to_buy;
         }
@@ -500,9 +893,6 @@ impl MarketplaceContract {
         // Update listing
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
 
-        // Mint NFT shares to the buyer
-        get_nft_client(&env).transfer_shares(&seller, &buyer.clone(), &listing_id, &shares_to_buy);
-
         // Create a transfer agreement
         let agreement_id: u64 = create_purchase_agreement(
             &env,
@@ -514,9 +904,79 @@ impl MarketplaceContract {
             &false,
         );
 
+        // Move the NFT shares to the buyer
+        get_nft_client(&env).settle_shares(
+            &seller,
+            &buyer.clone(),
+            &listing_id,
+            &shares_to_buy,
+            &agreement_id,
+        );
+
         agreement_id
     }
 
+    // Give `shares` of a listing's reserved shares to `beneficiary`, released linearly from
+    // `start` to `end` with nothing before `cliff`. Pass the same time for all three to lock the
+    // shares up until then. The creator may name themselves to vest their own reserved shares,
+    // which become available for sale as they are released.
+    pub fn grant_reserved_shares(
+        env: Env,
+        listing_id: u64,
+        beneficiary: Address,
+        shares: u32,
+        start: u64,
+        cliff: u64,
+        end: u64,
+    ) -> Result<(), Error> {
+        let mut listing: Listing = get_listing_by_id(&env, listing_id);
+        listing.creator.require_auth();
+
+        if shares == 0 || shares > listing.reserved_shares {
+            return Err(Error::InsufficientReservedShares);
+        }
+        if start > cliff || cliff > end {
+            return Err(Error::InvalidShareSchedule);
+        }
+
+        // One schedule per beneficiary and listing
+        let nft_client = get_nft_client(&env);
+        if nft_client
+            .share_schedule(&listing_id, &beneficiary)
+            .is_some()
+        {
+            return Err(Error::InvalidShareSchedule);
+        }
+
+        listing.reserved_shares -= shares;
+        if beneficiary == listing.creator {
+            listing.available_shares += shares;
+        } else {
+            nft_client.transfer_shares(
+                &env.current_contract_address(),
+                &listing.creator,
+                &beneficiary,
+                &listing_id,
+                &shares,
+            );
+        }
+        store_persistent(&env, &DataKey::Listing(listing_id), &listing);
+
+        nft_client.set_share_schedule(
+            &listing_id,
+            &beneficiary,
+            &ShareSchedule {
+                shares,
+                start,
+                cliff,
+                end,
+            },
+        );
+
+        MarketplaceEvent::ReservedSharesGranted(listing_id, beneficiary, shares).publish(&env);
+        Ok(())
+    }
+
     pub fn confirm_receipt(
         env: Env,
         renter_or_buyer: Address,
@@ -526,21 +986,452 @@ impl MarketplaceContract {
         renter_or_buyer.require_auth();
 
         let listing: Listing = get_listing_by_id(&env, listing_id);
+        // Taken before a sale hands every share to the buyer, the sellers are paid by shares
+        let holders: Map<Address, u32> = get_share_holders(&env, listing_id);
         complete_agreement(&env, renter_or_buyer.clone(), listing.clone(), is_rental);
 
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
         let payment_amount = get_escrow_client(&env).release(&listing_id);
 
-        distribute_dividends(&env, listing_id, payment_amount)?;
+        pay_shareholders(&env, listing_id, &holders, payment_amount);
         MarketplaceEvent::ConfirmedReceipt(listing_id, renter_or_buyer).publish(&env);
 
         Ok(())
     }
 
+    // Settle the price delta of an accepted agreement amendment, then apply it. While the
+    // escrow is still held the delta is added to it or refunded from it; once released, an
+    // increase is paid by the user straight to the owners and a decrease can't be settled.
+    // Amendments without a delta just apply, so anyone may settle them.
+    pub fn settle_amendment(env: Env, agreement_id: u64, version: u32) -> Result<(), Error> {
+        let agreement_client = get_agreement_client(&env);
+        let agreement: Agreement = agreement_client.get_agreement(&agreement_id);
+        let amendment: Amendment = agreement_client.get_amendment(&agreement_id, &version);
+        let listing_id: u64 = agreement.listing_id;
+
+        // The listing's escrow only covers this agreement if it still holds its payment
+        let escrow_client = get_escrow_client(&env);
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        let escrow_active: bool = listing.agreement_id == agreement_id
+            && match escrow_client.try_get_escrow(&listing_id) {
+                Ok(Ok(escrow)) => is_agreement_escrow(&escrow, &agreement),
+                _ => false,
+            };
+
+        let mut token_amount: i128 = 0;
+        match amendment.price_delta.cmp(&0) {
+            Ordering::Greater => {
+                agreement.user.require_auth();
+                if escrow_active {
+                    token_amount =
+                        top_up_escrow(&env, listing_id, amendment.price_delta, &agreement.user);
+                } else {
+                    token_amount = transfer_tokens_to_owner(
+                        &env,
+                        amendment.price_delta,
+                        &agreement.user,
+                        &env.current_contract_address(),
+                    );
+                    distribute_dividends(&env, listing_id, token_amount)?;
+                }
+            }
+            Ordering::Less => {
+                // The refund comes out of the owner's proceeds
+                agreement.owner.require_auth();
+                if !escrow_active {
+                    return Err(Error::AmendmentNotSettleable);
+                }
+                token_amount = -parse_amount(&env, &-amendment.price_delta);
+                escrow_client.adjust_amount(&listing_id, &token_amount);
+            }
+            Ordering::Equal => {}
+        }
+
+        agreement_client.settle_amendment(&agreement_id, &version);
+        Self::refresh_rental_control(env.clone(), listing_id);
+
+        MarketplaceEvent::AmendmentSettled(listing_id, agreement_id, version, token_amount)
+            .publish(&env);
+        Ok(())
+    }
+
+    // Either party pauses an active agreement; a paused lease gives the renter no control
+    pub fn pause_agreement(env: Env, caller: Address, agreement_id: u64) -> Result<(), Error> {
+        caller.require_auth();
+        let agreement_client = get_agreement_client(&env);
+        agreement_client.pause_agreement(&agreement_id, &caller);
+        sync_rental_control(&env, &agreement_client.get_agreement(&agreement_id));
+        Ok(())
+    }
+
+    // Resume a paused agreement and hand control back to the renter until the new end time
+    pub fn resume_agreement(env: Env, caller: Address, agreement_id: u64) -> Result<u64, Error> {
+        caller.require_auth();
+        let agreement_client = get_agreement_client(&env);
+        let end_time: u64 = agreement_client.resume_agreement(&agreement_id, &caller);
+        sync_rental_control(&env, &agreement_client.get_agreement(&agreement_id));
+        Ok(end_time)
+    }
+
+    // Re-sync the renter's control of the token with the listing's current lease, e.g. after a
+    // lease expired without the keeper running. Callable by anyone.
+    pub fn refresh_rental_control(env: Env, listing_id: u64) {
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        let agreement_client = get_agreement_client(&env);
+        if let Ok(Ok(agreement)) = agreement_client.try_get_agreement(&listing.agreement_id) {
+            sync_rental_control(&env, &agreement);
+        }
+    }
+
+    pub fn set_governance_config(
+        env: Env,
+        caller: Address,
+        quorum_bps: u32,
+        approval_bps: u32,
+        voting_period: u64,
+    ) -> Result<(), Error> {
+        require_role(&env, Role::Admin, &caller);
+
+        let config: GovernanceConfig = GovernanceConfig {
+            quorum_bps,
+            approval_bps,
+            voting_period,
+        };
+        if !is_valid_config(&config) {
+            return Err(Error::InvalidGovernanceConfig);
+        }
+        store_data(&env, &GOVERNANCE, &config);
+
+        MarketplaceEvent::GovernanceConfigUpdated(quorum_bps, approval_bps, voting_period)
+            .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_governance_config(env: Env) -> GovernanceConfig {
+        get_governance_config(&env)
+    }
+
+    // Any shareholder can put an action on a co-owned listing to a vote. Votes are weighted
+    // by the shares held when the proposal is created, so shares bought afterwards don't count.
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        listing_id: u64,
+        action: ProposalAction,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
+        get_listing_by_id(&env, listing_id);
+
+        let nft_client = get_nft_client(&env);
+        if nft_client.balance_of(&listing_id, &proposer) == 0 {
+            return Err(Error::NotAShareholder);
+        }
+
+        let config: GovernanceConfig = get_governance_config(&env);
+        let snapshot_id: u32 = nft_client.snapshot(&listing_id);
+        let current_time = env.ledger().timestamp();
+
+        let proposal: Proposal = Proposal {
+            id: next_proposal_id(&env),
+            listing_id,
+            proposer: proposer.clone(),
+            action,
+            snapshot_id,
+            total_shares: nft_client.total_supply_at(&listing_id, &snapshot_id),
+            votes_for: 0,
+            votes_against: 0,
+            created_at: current_time,
+            ends_at: current_time + config.voting_period,
+            status: ProposalStatus::Active,
+        };
+        store_proposal(&env, &proposal);
+
+        MarketplaceEvent::ProposalCreated(proposal.id, listing_id, proposer).publish(&env);
+        Ok(proposal.id)
+    }
+
+    // Executes the proposal as soon as the remaining shares can no longer defeat it
+    pub fn vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+        support: bool,
+    ) -> Result<ProposalStatus, Error> {
+        voter.require_auth();
+
+        let mut proposal: Proposal = utils::governance::get_proposal(&env, proposal_id)?;
+        if proposal.status != ProposalStatus::Active {
+            return Err(Error::ProposalNotActive);
+        }
+        if env.ledger().timestamp() >= proposal.ends_at {
+            return Err(Error::VotingClosed);
+        }
+
+        let vote_key: DataKey = DataKey::ProposalVote(proposal_id, voter.clone());
+        if has_persistent(&env, &vote_key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        let weight: u32 = get_nft_client(&env).balance_of_at(
+            &proposal.listing_id,
+            &voter,
+            &proposal.snapshot_id,
+        );
+        if weight == 0 {
+            return Err(Error::NotAShareholder);
+        }
+
+        if support {
+            proposal.votes_for += weight;
+        } else {
+            proposal.votes_against += weight;
+        }
+        store_persistent(&env, &vote_key, &support);
+        MarketplaceEvent::VoteCast(proposal_id, voter, support, weight).publish(&env);
+
+        if is_decided(&get_governance_config(&env), &proposal) {
+            proposal.status = match execute_action(&env, &proposal) {
+                Ok(()) => ProposalStatus::Executed,
+                Err(_) => ProposalStatus::Failed,
+            };
+            MarketplaceEvent::ProposalFinalized(proposal_id, proposal.status).publish(&env);
+        }
+        store_proposal(&env, &proposal);
+
+        Ok(proposal.status)
+    }
+
+    // Close a proposal once voting has ended, executing it if it passed. Callable by anyone.
+    pub fn finalize_proposal(env: Env, proposal_id: u64) -> Result<ProposalStatus, Error> {
+        let mut proposal: Proposal = utils::governance::get_proposal(&env, proposal_id)?;
+        if proposal.status != ProposalStatus::Active {
+            return Err(Error::ProposalNotActive);
+        }
+        if env.ledger().timestamp() < proposal.ends_at {
+            return Err(Error::VotingNotEnded);
+        }
+
+        // A passed proposal whose action can't run is closed as failed rather than left open
+        if has_passed(&get_governance_config(&env), &proposal) {
+            proposal.status = match execute_action(&env, &proposal) {
+                Ok(()) => ProposalStatus::Executed,
+                Err(_) => ProposalStatus::Failed,
+            };
+        } else {
+            proposal.status = ProposalStatus::Defeated;
+        }
+        store_proposal(&env, &proposal);
+
+        MarketplaceEvent::ProposalFinalized(proposal_id, proposal.status).publish(&env);
+        Ok(proposal.status)
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        utils::governance::get_proposal(&env, proposal_id)
+    }
+
+    // Add payment tokens to a listing's reserve fund, paid out to shareholders by proposal
+    pub fn deposit_reserve(env: Env, from: Address, listing_id: u64, amount: i128) {
+        from.require_auth();
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        if listing.status == ListingStatus::Decommissioned {
+            panic_with_error!(&env, Error::ListingNotAvailable);
+        }
+
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidPrice);
+        }
+
+        let token_addr: Address = get_data(&env, &PAYMENT_TOKEN).unwrap();
+        token::Client::new(&env, &token_addr).transfer(
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let reserve: i128 = Self::get_reserve(env.clone(), listing_id);
+        store_persistent(&env, &DataKey::ReserveFund(listing_id), &(reserve + amount));
+
+        MarketplaceEvent::ReserveDeposited(listing_id, from, amount).publish(&env);
+    }
+
+    pub fn get_reserve(env: Env, listing_id: u64) -> i128 {
+        get_persistent(&env, &DataKey::ReserveFund(listing_id)).unwrap_or(0)
+    }
+
+    pub fn set_drag_along_threshold(
+        env: Env,
+        caller: Address,
+        threshold_bps: u32,
+    ) -> Result<(), Error> {
+        require_role(&env, Role::Admin, &caller);
+
+        if threshold_bps == 0 || threshold_bps > 10_000 {
+            return Err(Error::InvalidDragAlongThreshold);
+        }
+        store_data(&env, &DRAG_ALONG, &threshold_bps);
+
+        MarketplaceEvent::DragAlongUpdated(threshold_bps).publish(&env);
+        Ok(())
+    }
+
+    pub fn get_drag_along_threshold(env: Env) -> u32 {
+        get_drag_along_bps(&env)
+    }
+
+    // Offer `price` (in listing currency) for all shares of a listing. The payment is taken
+    // now and held until enough holders accept to drag the rest along, or the offer is
+    // cancelled.
+    pub fn offer_buyout(
+        env: Env,
+        buyer: Address,
+        listing_id: u64,
+        price: i128,
+        expires_at: u64,
+    ) -> Result<u64, Error> {
+        buyer.require_auth();
+        require_verified(&env, &buyer, get_listing_kyc_level(&env, listing_id));
+
+        let listing: Listing = get_listing_by_id(&env, listing_id);
+        if listing.status != ListingStatus::Available {
+            return Err(Error::ListingNotAvailable);
+        }
+        if has_persistent(&env, &DataKey::ListingBuyout(listing_id)) {
+            return Err(Error::BuyoutPending);
+        }
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(Error::BuyoutExpired);
+        }
+
+        let token_amount: i128 =
+            transfer_tokens_to_owner(&env, price, &buyer, &env.current_contract_address());
+
+        let offer_id: u64 = get_data(&env, &DataKey::BuyoutCount).unwrap_or(0) + 1;
+        store_data(&env, &DataKey::BuyoutCount, &offer_id);
+
+        let offer: BuyoutOffer = BuyoutOffer {
+            id: offer_id,
+            listing_id,
+            buyer: buyer.clone(),
+            token_amount,
+            total_shares: get_nft_client(&env).total_supply(&listing_id),
+            accepted: Map::new(&env),
+            accepted_shares: 0,
+            expires_at,
+            status: BuyoutStatus::Open,
+        };
+        store_buyout(&env, &offer);
+        store_persistent(&env, &DataKey::ListingBuyout(listing_id), &offer_id);
+
+        MarketplaceEvent::BuyoutOffered(offer_id, listing_id, buyer, token_amount).publish(&env);
+        Ok(offer_id)
+    }
+
+    // A holder accepts with all their shares, which the marketplace holds until the offer
+    // closes. Completes the buyout once the drag-along threshold is reached.
+    pub fn accept_buyout(env: Env, holder: Address, offer_id: u64) -> Result<BuyoutStatus, Error> {
+        holder.require_auth();
+
+        let mut offer: BuyoutOffer = get_buyout(&env, offer_id)?;
+        if offer.status != BuyoutStatus::Open {
+            return Err(Error::BuyoutNotOpen);
+        }
+        if env.ledger().timestamp() >= offer.expires_at {
+            return Err(Error::BuyoutExpired);
+        }
+        // The buyer's verification may have lapsed since the offer was made
+        require_verified(&env, &offer.buyer, get_listing_kyc_level(&env, offer.listing_id));
+
+        let nft_client = get_nft_client(&env);
+        let marketplace: Address = env.current_contract_address();
+        if holder == offer.buyer || nft_client.balance_of(&offer.listing_id, &holder) == 0 {
+            return Err(Error::NotAShareholder);
+        }
+        // Only vested shares can be sold, locked ones stay with the holder
+        let shares: u32 = nft_client.vested_balance(&offer.listing_id, &holder);
+        if shares == 0 {
+            return Err(Error::SharesLocked);
+        }
+        nft_client.transfer_shares(&marketplace, &holder, &marketplace, &offer.listing_id, &shares);
+
+        offer
+            .accepted
+            .set(holder.clone(), offer.accepted.get(holder.clone()).unwrap_or(0) + shares);
+        offer.accepted_shares += shares;
+        MarketplaceEvent::BuyoutAccepted(offer_id, holder, shares).publish(&env);
+
+        if is_drag_along_reached(&env, &offer) {
+            complete_buyout(&env, &mut offer);
+        } else {
+            store_buyout(&env, &offer);
+        }
+
+        Ok(offer.status)
+    }
+
+    // The buyer can withdraw an open offer at any time, anyone can close it once it expired
+    pub fn cancel_buyout(env: Env, offer_id: u64) -> Result<(), Error> {
+        let mut offer: BuyoutOffer = get_buyout(&env, offer_id)?;
+        if offer.status != BuyoutStatus::Open {
+            return Err(Error::BuyoutNotOpen);
+        }
+        if env.ledger().timestamp() < offer.expires_at {
+            offer.buyer.require_auth();
+        }
+
+        cancel_buyout(&env, &mut offer);
+        Ok(())
+    }
+
+    pub fn get_buyout(env: Env, offer_id: u64) -> Result<BuyoutOffer, Error> {
+        get_buyout(&env, offer_id)
+    }
+
+    // The creator retires a listing whose asset was sold off-platform or destroyed, depositing
+    // `proceeds` payment tokens for the holders to redeem their shares against
+    pub fn decommission(env: Env, listing_id: u64, proceeds: i128) -> Result<(), Error> {
+        let mut listing: Listing = get_listing_by_id(&env, listing_id);
+        listing.creator.require_auth();
+
+        if proceeds < 0 {
+            return Err(Error::InvalidPrice);
+        }
+        if proceeds > 0 {
+            let token_addr: Address = get_data(&env, &PAYMENT_TOKEN).unwrap();
+            token::Client::new(&env, &token_addr).transfer(
+                &listing.creator,
+                &env.current_contract_address(),
+                &proceeds,
+            );
+        }
+
+        decommission_listing(&env, &mut listing, proceeds)?;
+        store_persistent(&env, &DataKey::Listing(listing_id), &listing);
+        Ok(())
+    }
+
+    // Exchange all of the holder's shares in a decommissioned listing for their pro-rata part
+    // of the proceeds. Returns the payout in payment tokens.
+    pub fn redeem_shares(env: Env, holder: Address, listing_id: u64) -> Result<i128, Error> {
+        holder.require_auth();
+        redeem_shares(&env, &holder, listing_id)
+    }
+
+    pub fn get_redemption_pool(env: Env, listing_id: u64) -> i128 {
+        get_redemption_pool(&env, listing_id)
+    }
+
     pub fn cancel_sale_or_rental(env: Env, seller: Address, listing_id: u64) -> Result<(), Error> {
         seller.require_auth();
+        if is_co_owned(&env, listing_id) {
+            return Err(Error::ListingCoOwned);
+        }
         let mut listing: Listing = get_listing_by_id(&env, listing_id);
         terminate_agreement(&env, &listing.agreement_id, &listing_id, &seller);
+        Self::refresh_rental_control(env.clone(), listing_id);
 
         listing.status = ListingStatus::Available;
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
@@ -550,11 +1441,22 @@ impl MarketplaceContract {
         Ok(())
     }
 
-    // Owner calls this to confirm renter has returned item and agreement has been reached
+    // Owner calls this to confirm renter has returned item and agreement has been reached.
+    // Co-owned listings are reclaimed by a `ReclaimAsset` proposal instead.
     pub fn reclaim_or_return(env: Env, seller: Address, listing_id: u64) -> Result<(), Error> {
         seller.require_auth();
+        if is_co_owned(&env, listing_id) {
+            return Err(Error::ListingCoOwned);
+        }
         let mut listing: Listing = get_listing_by_id(&env, listing_id);
-        get_agreement_client(&env).complete_agreement(&listing.agreement_id, &seller);
+        let agreement_client = get_agreement_client(&env);
+
+        // Only the owner confirms the item is back, a renter can't end the lease on their own
+        if agreement_client.get_agreement(&listing.agreement_id).owner != seller {
+            return Err(Error::AgreementNotOwnedByCaller);
+        }
+        agreement_client.complete_agreement(&listing.agreement_id, &seller);
+        Self::refresh_rental_control(env.clone(), listing_id);
         listing.status = ListingStatus::Available;
         store_persistent(&env, &DataKey::Listing(listing_id), &listing);
 
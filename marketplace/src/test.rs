@@ -10,8 +10,12 @@ use common::pricefeed::interface::PriceOracleContractClient;
 use escrow::EscrowContract;
 use nft::contract::NFTContract;
 use price_feed::PriceOracleContract;
+use reflector_oracle_mock::{
+    Asset as OracleAsset, MockPriceOracleContract, MockPriceOracleContractClient,
+};
 use soroban_sdk::testutils::{Address as _, StellarAssetContract};
-use soroban_sdk::{token, Address};
+use common::agreement::types::Agreement;
+use soroban_sdk::{token, Address, BytesN};
 
 fn create_marketplace_contract<'a>(env: &Env) -> MarketplaceContractClient<'a> {
     let contract_id = env.register(MarketplaceContract, ());
@@ -45,6 +49,13 @@ fn create_price_feed_contract<'a>(env: &Env) -> PriceOracleContractClient<'a> {
     contract_client
 }
 
+fn create_reflector_contract<'a>(env: &Env) -> MockPriceOracleContractClient<'a> {
+    let contract_id: Address = env.register(MockPriceOracleContract, ());
+    let contract_client: MockPriceOracleContractClient<'_> =
+        MockPriceOracleContractClient::new(env, &contract_id);
+    contract_client
+}
+
 fn create_token_contract<'a>(
     e: &Env,
     admin: &Address,
@@ -56,6 +67,9 @@ fn create_token_contract<'a>(
     )
 }
 
+// 1_000 in 7 decimals
+pub const LISTING_PRICE: i128 = 10_000_000_000;
+
 pub struct MarketplaceTest {
     env: Env,
     marketplace_client: MarketplaceContractClient<'static>,
@@ -64,6 +78,7 @@ pub struct MarketplaceTest {
     escrow_client: EscrowContractClient<'static>,
     token_client: token::TokenClient<'static>,
     price_feed_client: PriceOracleContractClient<'static>,
+    reflector_client: MockPriceOracleContractClient<'static>,
     alice: Address,
     bob: Address,
     admin: Address,
@@ -73,8 +88,7 @@ impl MarketplaceTest {
     fn setup() -> Self {
         let env: Env = Env::default();
         let test = Self::setup_no_init(env.clone());
-        let reflector_ca: Address = Address::generate(&env);
-        let payment_token: Address = Address::generate(&env);
+        let payment_token: Address = test.token_client.address.clone();
 
         let initial_rate: i128 = 1612_0000000;
 
@@ -84,7 +98,7 @@ impl MarketplaceTest {
             &test.agreement_client.address,
             &test.escrow_client.address,
             &test.price_feed_client.address,
-            &reflector_ca,
+            &test.reflector_client.address,
             &payment_token
         );
         test.nft_client
@@ -100,6 +114,12 @@ impl MarketplaceTest {
             &1u64,
             &10000_i128,
         );
+        // USDC at $1.00 with the oracle's 14 decimals
+        test.reflector_client.set_price(
+            &OracleAsset::Other(Symbol::new(&env, "USDC")),
+            &100_000_000_000_000_i128,
+            &0u64,
+        );
 
         return test;
     }
@@ -112,6 +132,7 @@ impl MarketplaceTest {
         let escrow_client: EscrowContractClient<'_> = create_escrow_contract(&env);
         let nft_client: NFTContractClient<'_> = create_nft_contract(&env);
         let price_feed_client: PriceOracleContractClient<'_> = create_price_feed_contract(&env);
+        let reflector_client: MockPriceOracleContractClient<'_> = create_reflector_contract(&env);
 
         // Generate the accounts (users)
         let alice: Address = Address::generate(&env);
@@ -133,12 +154,57 @@ impl MarketplaceTest {
             escrow_client,
             token_client,
             price_feed_client,
+            reflector_client,
             alice,
             bob,
             admin,
         };
     }
+
+    // Alice lists the test item for sale and hourly rent at LISTING_PRICE, split into 1_000 shares
+    fn create_default_listing(&self, reserved_shares: u32) -> u64 {
+        self.marketplace_client.create_listing(
+            &self.alice,
+            &String::from_str(&self.env, "acy23bza"),
+            &String::from_str(&self.env, "https://gearup.market/listings/acy23bza"),
+            &LISTING_PRICE,
+            &3600u64,
+            &true,
+            &true,
+            &1_000u32,
+            &reserved_shares,
+            &0u32,
+        )
+    }
+
+    // The owner attaches the standard terms to an agreement and both parties sign them
+    fn accept_terms(&self, agreement_id: u64) {
+        let agreement: Agreement = self.agreement_client.get_agreement(&agreement_id);
+        let terms_hash: BytesN<32> = BytesN::from_array(&self.env, &[1u8; 32]);
+
+        self.agreement_client
+            .attach_terms(&agreement_id, &agreement.owner, &terms_hash, &1u32);
+        self.agreement_client
+            .sign_terms(&agreement_id, &agreement.owner, &terms_hash);
+        self.agreement_client
+            .sign_terms(&agreement_id, &agreement.user, &terms_hash);
+    }
 }
 
+mod access_control;
+mod agreement_lifecycle;
+mod buyout;
 mod create_listing;
+mod governance;
+mod identity;
+mod price_source;
+mod provenance;
 mod purchase_or_rent;
+mod redemption;
+mod reputation;
+mod share_approvals;
+mod snapshots;
+mod subscription;
+mod token_metadata;
+mod token_users;
+mod vesting;
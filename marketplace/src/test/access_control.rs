@@ -0,0 +1,144 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use crate::types::{Currency, ListingStatus};
+use common::access::{
+    interface::AccessControlClient,
+    types::{AccessError, Role},
+};
+use common::pricefeed::types::OracleError;
+use escrow::{EscrowContract, ADMIN};
+use soroban_sdk::Address;
+
+#[test]
+fn test_roles_delegate_admin_operations() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+
+    assert!(test.marketplace_client.has_role(&Role::OracleManager, &test.admin));
+    assert!(!test.marketplace_client.has_role(&Role::OracleManager, &test.alice));
+    assert!(test
+        .marketplace_client
+        .try_set_emergency_price(&test.alice, &1500_0000000i128, &3600u64)
+        .is_err());
+
+    // An oracle manager runs the price sources but nothing else
+    test.marketplace_client
+        .grant_role(&test.admin, &Role::OracleManager, &test.alice);
+    assert!(test.marketplace_client.has_role(&Role::OracleManager, &test.alice));
+    test.marketplace_client
+        .set_emergency_price(&test.alice, &1500_0000000i128, &3600u64);
+    assert!(test
+        .marketplace_client
+        .try_set_currency(&test.alice, &Currency::USDC)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_grant_role(&test.alice, &Role::OracleManager, &test.bob)
+        .is_err());
+
+    test.marketplace_client
+        .renounce_role(&test.alice, &Role::OracleManager);
+    assert!(!test.marketplace_client.has_role(&Role::OracleManager, &test.alice));
+    assert!(test
+        .marketplace_client
+        .try_clear_emergency_price(&test.alice)
+        .is_err());
+
+    // A pauser may stop the price feed, only an oracle manager may start it again
+    let feed_access: AccessControlClient<'_> =
+        AccessControlClient::new(&test.env, &test.price_feed_client.address);
+    feed_access.grant_role(&test.admin, &Role::Pauser, &test.bob);
+    test.price_feed_client.trip_circuit_breaker(&test.bob);
+    assert!(test.price_feed_client.is_circuit_breaker_tripped());
+    assert_eq!(
+        test.price_feed_client.try_reset_circuit_breaker(&test.bob),
+        Err(Ok(OracleError::Unauthorized))
+    );
+
+    feed_access.revoke_role(&test.admin, &Role::Pauser, &test.bob);
+    assert!(!feed_access.has_role(&Role::Pauser, &test.bob));
+    test.price_feed_client.reset_circuit_breaker(&test.admin);
+    assert!(!test.price_feed_client.is_circuit_breaker_tripped());
+}
+
+#[test]
+fn test_listing_repairs_need_admin() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    assert!(test
+        .marketplace_client
+        .try_change_listing_status(&test.alice, &listing_id, &ListingStatus::Unavailable)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_update_listing_count(&test.bob, &0u64)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_remove_listing(&test.bob, &listing_id)
+        .is_err());
+    assert_eq!(test.marketplace_client.get_listing_count(), 1);
+
+    test.marketplace_client
+        .change_listing_status(&test.admin, &listing_id, &ListingStatus::Unavailable);
+    assert!(
+        test.marketplace_client.get_listing(&listing_id).status == ListingStatus::Unavailable
+    );
+    test.marketplace_client
+        .remove_listing(&test.admin, &listing_id);
+    assert!(test.marketplace_client.try_get_listing(&listing_id).is_err());
+}
+
+#[test]
+fn test_last_admin_cannot_leave() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let nft_access: AccessControlClient<'_> =
+        AccessControlClient::new(&test.env, &test.nft_client.address);
+
+    assert!(nft_access
+        .try_renounce_role(&test.admin, &Role::Admin)
+        .is_err());
+
+    // Hand administration over to alice
+    nft_access.grant_role(&test.admin, &Role::Admin, &test.alice);
+    nft_access.renounce_role(&test.admin, &Role::Admin);
+    assert!(!nft_access.has_role(&Role::Admin, &test.admin));
+    assert!(nft_access
+        .try_grant_role(&test.admin, &Role::Upgrader, &test.bob)
+        .is_err());
+
+    assert!(nft_access
+        .try_revoke_role(&test.alice, &Role::Admin, &test.alice)
+        .is_err());
+    nft_access.grant_role(&test.alice, &Role::Upgrader, &test.bob);
+    assert!(nft_access.has_role(&Role::Upgrader, &test.bob));
+    assert!(!nft_access.has_role(&Role::Admin, &test.bob));
+}
+
+#[test]
+fn test_upgraded_contract_bootstraps_roles_once() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+
+    // An escrow deployed before roles existed only stored its admin
+    let escrow_id: Address = test.env.register(EscrowContract, ());
+    let escrow_client: AccessControlClient<'_> = AccessControlClient::new(&test.env, &escrow_id);
+    test.env.as_contract(&escrow_id, || {
+        test.env.storage().instance().set(&ADMIN, &test.admin);
+    });
+    assert!(!escrow_client.has_role(&Role::Upgrader, &test.admin));
+
+    escrow_client.bootstrap_roles();
+    assert!(escrow_client.has_role(&Role::Admin, &test.admin));
+    assert!(escrow_client.has_role(&Role::Upgrader, &test.admin));
+    assert_eq!(
+        escrow_client.try_bootstrap_roles(),
+        Err(Ok(AccessError::AlreadyBootstrapped.into()))
+    );
+
+    // Contracts initialized with roles have nothing to bootstrap
+    assert!(test.marketplace_client.try_bootstrap_roles().is_err());
+    assert!(AccessControlClient::new(&test.env, &test.price_feed_client.address)
+        .try_bootstrap_roles()
+        .is_err());
+}
@@ -0,0 +1,507 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::Error;
+use common::agreement::types::{
+    Agreement, AgreementPage, AgreementRole, AgreementStatus, AgreementType,
+    AmendmentStatus, DataKey, Error as AgreementError,
+};
+use soroban_sdk::testutils::Ledger;
+use soroban_sdk::{vec, BytesN};
+
+#[test]
+fn test_rental_lifecycle_history() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Active
+    );
+    assert!(test.nft_client.has_control(&listing_id, &test.bob));
+
+    // Paused for 100 seconds pushes the end time back by the same amount, the renter has no
+    // control in between
+    test.marketplace_client
+        .pause_agreement(&test.alice, &agreement_id);
+    assert!(!test.nft_client.has_control(&listing_id, &test.bob));
+    assert!(test
+        .marketplace_client
+        .try_resume_agreement(&test.bob, &agreement_id)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_reclaim_or_return(&test.alice, &listing_id)
+        .is_err());
+
+    test.env.ledger().set_timestamp(100);
+    test.marketplace_client
+        .resume_agreement(&test.alice, &agreement_id);
+    let agreement: Agreement = test.agreement_client.get_agreement(&agreement_id);
+    assert!(agreement.end_time == Some(duration + 100));
+    assert!(test.nft_client.has_control(&listing_id, &test.bob));
+
+    // The owner can end a pause the renter started
+    test.marketplace_client
+        .pause_agreement(&test.bob, &agreement_id);
+    test.marketplace_client
+        .resume_agreement(&test.alice, &agreement_id);
+
+    test.marketplace_client
+        .reclaim_or_return(&test.alice, &listing_id);
+    assert!(!test.nft_client.has_control(&listing_id, &test.bob));
+
+    let history = test.agreement_client.get_agreement_history(&agreement_id);
+    let statuses = [
+        AgreementStatus::Created,
+        AgreementStatus::Active,
+        AgreementStatus::Paused,
+        AgreementStatus::Active,
+        AgreementStatus::Paused,
+        AgreementStatus::Active,
+        AgreementStatus::Completed,
+    ];
+    assert_eq!(history.len(), statuses.len() as u32);
+    for (entry, status) in history.iter().zip(statuses.iter()) {
+        assert!(entry.status == *status);
+    }
+    assert!(history.get(2).unwrap().role == AgreementRole::Owner);
+    assert!(history.get(4).unwrap().role == AgreementRole::User);
+    assert!(history.get(5).unwrap().role == AgreementRole::Owner);
+    assert_eq!(history.get(3).unwrap().timestamp, 100);
+    let completed = history.get(6).unwrap();
+    assert!(completed.role == AgreementRole::Owner);
+    assert_eq!(completed.actor, test.alice);
+
+    // Completed agreements can't be terminated
+    assert!(test
+        .agreement_client
+        .try_terminate_agreement(&agreement_id, &test.alice)
+        .is_err());
+}
+
+#[test]
+fn test_terms_require_both_signatures() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+    let terms_hash: BytesN<32> = BytesN::from_array(&test.env, &[7u8; 32]);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+
+    // No rental starts without terms
+    assert!(test
+        .marketplace_client
+        .try_confirm_receipt(&test.bob, &listing_id, &true)
+        .is_err());
+
+    test.agreement_client
+        .attach_terms(&agreement_id, &test.alice, &terms_hash, &1u32);
+    assert!(!test
+        .agreement_client
+        .sign_terms(&agreement_id, &test.bob, &terms_hash));
+    assert_eq!(
+        test.agreement_client.get_terms_signers(&agreement_id),
+        vec![&test.env, test.bob.clone()]
+    );
+
+    // Once the renter signed, the owner can't swap the terms from under them
+    let other_hash: BytesN<32> = BytesN::from_array(&test.env, &[8u8; 32]);
+    assert_eq!(
+        test.agreement_client
+            .try_attach_terms(&agreement_id, &test.alice, &other_hash, &2u32),
+        Err(Ok(AgreementError::TermsAlreadySigned))
+    );
+
+    // Owner hasn't accepted yet, so the rental can't start
+    assert!(test
+        .marketplace_client
+        .try_confirm_receipt(&test.bob, &listing_id, &true)
+        .is_err());
+
+    assert!(test
+        .agreement_client
+        .try_sign_terms(&agreement_id, &test.alice, &other_hash)
+        .is_err());
+    assert!(test
+        .agreement_client
+        .sign_terms(&agreement_id, &test.alice, &terms_hash));
+
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Active
+    );
+}
+
+#[test]
+fn test_purchase_terms_signed_before_completion() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let terms_hash: BytesN<32> = BytesN::from_array(&test.env, &[7u8; 32]);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 = test.marketplace_client.purchase(&listing_id, &test.bob);
+    assert!(test
+        .marketplace_client
+        .try_confirm_receipt(&test.bob, &listing_id, &false)
+        .is_err());
+
+    // The buyer can propose terms too, and the purchase can't complete until both signed
+    test.agreement_client
+        .attach_terms(&agreement_id, &test.bob, &terms_hash, &1u32);
+    test.agreement_client
+        .sign_terms(&agreement_id, &test.bob, &terms_hash);
+    assert!(test
+        .marketplace_client
+        .try_confirm_receipt(&test.bob, &listing_id, &false)
+        .is_err());
+
+    test.agreement_client
+        .sign_terms(&agreement_id, &test.alice, &terms_hash);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &false);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id)
+            == AgreementStatus::Completed
+    );
+}
+
+#[test]
+fn test_amendment_settled_through_escrow() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let delta: i128 = 500_0000000;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+
+    // Renter asks to double the rental for half the price again
+    let version: u32 = test.agreement_client.propose_amendment(
+        &agreement_id,
+        &test.bob,
+        &Some(duration * 2),
+        &None,
+        &delta,
+    );
+    assert!(test
+        .agreement_client
+        .try_accept_amendment(&agreement_id, &test.bob, &version)
+        .is_err());
+    assert!(
+        test.agreement_client
+            .accept_amendment(&agreement_id, &test.alice, &version)
+            == AmendmentStatus::Accepted
+    );
+
+    // Not applied until the delta is settled
+    let agreement: Agreement = test.agreement_client.get_agreement(&agreement_id);
+    assert!(agreement.duration == Some(duration));
+
+    test.marketplace_client
+        .settle_amendment(&agreement_id, &version);
+
+    let agreement: Agreement = test.agreement_client.get_agreement(&agreement_id);
+    assert!(agreement.duration == Some(duration * 2));
+    assert!(agreement.end_time == Some(duration * 2));
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance - delta);
+    assert_eq!(
+        test.escrow_client.get_escrow(&listing_id).amount,
+        price + delta
+    );
+
+    let amendments = test.agreement_client.get_amendments(&agreement_id);
+    assert_eq!(amendments.len(), 1);
+    assert!(amendments.get(0).unwrap().status == AmendmentStatus::Applied);
+    assert!(test
+        .marketplace_client
+        .try_settle_amendment(&agreement_id, &version)
+        .is_err());
+}
+
+#[test]
+fn test_refund_amendment_needs_owner_and_escrow() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+    let refund: i128 = 250_0000000;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+
+    // Owner shortens the rental and hands part of the payment back
+    let version: u32 = test.agreement_client.propose_amendment(
+        &agreement_id,
+        &test.alice,
+        &Some(duration / 2),
+        &None,
+        &-refund,
+    );
+    test.agreement_client
+        .accept_amendment(&agreement_id, &test.bob, &version);
+    test.marketplace_client
+        .settle_amendment(&agreement_id, &version);
+    assert!(test
+        .env
+        .auths()
+        .iter()
+        .any(|(address, _)| *address == test.alice));
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance + refund);
+    assert_eq!(
+        test.escrow_client.get_escrow(&listing_id).amount,
+        price - refund
+    );
+
+    // Once the escrow is released there's nothing left to refund from
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    let version: u32 = test.agreement_client.propose_amendment(
+        &agreement_id,
+        &test.alice,
+        &None,
+        &Some(duration),
+        &-refund,
+    );
+    test.agreement_client
+        .accept_amendment(&agreement_id, &test.bob, &version);
+    assert_eq!(
+        test.marketplace_client
+            .try_settle_amendment(&agreement_id, &version),
+        Err(Ok(Error::AmendmentNotSettleable))
+    );
+}
+
+#[test]
+fn test_amendment_without_delta_moves_control() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    assert_eq!(test.nft_client.user_expires(&listing_id, &test.bob), duration);
+
+    // A free extension still waits for settlement so the renter's control follows it
+    let version: u32 = test.agreement_client.propose_amendment(
+        &agreement_id,
+        &test.bob,
+        &None,
+        &Some(duration * 3),
+        &0_i128,
+    );
+    assert!(
+        test.agreement_client
+            .accept_amendment(&agreement_id, &test.alice, &version)
+            == AmendmentStatus::Accepted
+    );
+    test.marketplace_client
+        .settle_amendment(&agreement_id, &version);
+    assert_eq!(
+        test.nft_client.user_expires(&listing_id, &test.bob),
+        duration * 3
+    );
+
+    // Purchases have no timing to amend
+    let purchase_listing: u64 = test.create_default_listing(100u32);
+    let purchase_id: u64 = test.marketplace_client.purchase(&purchase_listing, &test.bob);
+    assert!(test
+        .agreement_client
+        .try_propose_amendment(&purchase_id, &test.bob, &Some(duration), &None, &0_i128)
+        .is_err());
+}
+
+#[test]
+fn test_agreement_pages() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    test.marketplace_client
+        .subscribe(&listing_id, &test.bob, &duration, &0u64);
+    test.marketplace_client
+        .subscribe(&listing_id, &test.bob, &duration, &0u64);
+    let lease_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+
+    let subscriptions: Option<AgreementType> = Some(AgreementType::Subscription);
+    let page: AgreementPage =
+        test.agreement_client
+            .get_user_agreements(&test.bob, &None, &subscriptions, &0u32, &1u32);
+    assert_eq!(page.agreements.len(), 1);
+    assert_eq!(page.next_cursor, Some(1));
+
+    let page: AgreementPage =
+        test.agreement_client
+            .get_user_agreements(&test.bob, &None, &subscriptions, &1u32, &10u32);
+    assert_eq!(page.agreements.len(), 1);
+    assert_eq!(page.next_cursor, None);
+
+    let page: AgreementPage = test.agreement_client.get_listing_agreements(
+        &listing_id,
+        &Some(AgreementStatus::Created),
+        &None,
+        &0u32,
+        &10u32,
+    );
+    assert_eq!(page.agreements.len(), 1);
+    assert_eq!(page.agreements.get(0).unwrap().id, lease_id);
+
+    // An empty page isn't asked for by accident
+    let page: AgreementPage =
+        test.agreement_client
+            .get_user_agreements(&test.bob, &None, &None, &0u32, &0u32);
+    assert_eq!(page.agreements.len(), 1);
+}
+
+#[test]
+fn test_version_one_agreements_migrated() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    // Version 1 kept agreements and their indexes in instance storage
+    let legacy: Agreement = Agreement {
+        id: 1,
+        user: test.bob.clone(),
+        owner: test.alice.clone(),
+        listing_id,
+        timestamp: 0,
+        shares: 0,
+        duration: Some(3600),
+        end_time: Some(3600),
+        status: AgreementStatus::Active,
+        agreement_type: AgreementType::Lease,
+    };
+    test.env.as_contract(&test.agreement_client.address, || {
+        let storage = test.env.storage().instance();
+        storage.set(&DataKey::Agreement(1), &legacy);
+        storage.set(&DataKey::UserAgreements(test.bob.clone()), &vec![&test.env, 1u64]);
+        storage.set(&DataKey::AgreementCount, &1u64);
+    });
+    assert!(test.agreement_client.try_get_agreement(&1u64).is_err());
+
+    assert_eq!(test.agreement_client.version(), 2);
+    assert_eq!(test.agreement_client.migrate(&test.admin, &0u32), 1);
+    assert_eq!(test.agreement_client.migrate(&test.admin, &10u32), 0);
+    assert!(test.agreement_client.get_agreement(&1u64).user == test.bob);
+    let page: AgreementPage =
+        test.agreement_client
+            .get_user_agreements(&test.bob, &None, &None, &0u32, &10u32);
+    assert_eq!(page.agreements.len(), 1);
+
+    // Live leases come out indexed for the expiry sweep
+    test.env.ledger().set_timestamp(3600);
+    assert_eq!(test.agreement_client.get_expired_leases(&10u32), vec![&test.env, 1u64]);
+}
+
+#[test]
+fn test_expired_rental_swept() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &duration);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    assert!(test.nft_client.has_control(&listing_id, &test.bob));
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 0);
+
+    // The renter can't close the lease without the owner confirming the return
+    assert_eq!(
+        test.marketplace_client
+            .try_reclaim_or_return(&test.bob, &listing_id),
+        Err(Ok(Error::AgreementNotOwnedByCaller))
+    );
+    assert!(test.nft_client.has_control(&listing_id, &test.bob));
+
+    test.env.ledger().set_timestamp(duration);
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 1);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Overdue
+    );
+    assert!(!test.nft_client.has_control(&listing_id, &test.bob));
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 0);
+
+    test.marketplace_client
+        .reclaim_or_return(&test.alice, &listing_id);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Completed
+    );
+}
+
+#[test]
+fn test_expiry_index_follows_end_time() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let hour: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &hour);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+
+    // Paused leases aren't due, resuming moves them to their new end time
+    test.marketplace_client
+        .pause_agreement(&test.alice, &agreement_id);
+    test.env.ledger().set_timestamp(hour);
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 0);
+    test.marketplace_client
+        .resume_agreement(&test.alice, &agreement_id);
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 0);
+    test.env.ledger().set_timestamp(2 * hour);
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 1);
+    test.marketplace_client
+        .reclaim_or_return(&test.alice, &listing_id);
+
+    // After a long quiet spell the sweep catches up a bounded number of buckets per call
+    test.env.ledger().set_timestamp(200 * hour);
+    let next_agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &price, &hour);
+    test.accept_terms(next_agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    test.env.ledger().set_timestamp(201 * hour);
+
+    let mut sweeps: u32 = 1;
+    while test.marketplace_client.process_expired(&10u32) == 0 {
+        sweeps += 1;
+    }
+    assert!(sweeps > 1);
+    assert!(
+        test.agreement_client.get_agreement_status(&next_agreement_id) == AgreementStatus::Overdue
+    );
+}
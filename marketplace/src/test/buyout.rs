@@ -0,0 +1,151 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::{BuyoutStatus, Error, Listing};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{vec, Address};
+
+#[test]
+fn test_buyout_with_drag_along() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let price: i128 = LISTING_PRICE;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &300u32);
+    test.marketplace_client
+        .set_drag_along_threshold(&test.admin, &7_000u32);
+
+    // A plain purchase would skip paying the co-owners
+    assert!(test
+        .marketplace_client
+        .try_purchase(&listing_id, &test.bob)
+        .is_err());
+
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&price);
+
+    // Cancelled offers hand shares back and refund the buyer
+    let offer_id: u64 =
+        test.marketplace_client
+            .offer_buyout(&test.bob, &listing_id, &price, &1_000u64);
+    assert!(test
+        .marketplace_client
+        .try_offer_buyout(&test.bob, &listing_id, &price, &1_000u64)
+        .is_err());
+    assert!(
+        test.marketplace_client.accept_buyout(&carol, &offer_id) == BuyoutStatus::Open
+    );
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 0);
+    test.marketplace_client.cancel_buyout(&offer_id);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 300);
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance);
+
+    // 70% accepting drags the remaining 30% along
+    let offer_id: u64 =
+        test.marketplace_client
+            .offer_buyout(&test.bob, &listing_id, &price, &1_000u64);
+    assert!(
+        test.marketplace_client.accept_buyout(&test.alice, &offer_id)
+            == BuyoutStatus::Completed
+    );
+
+    assert_eq!(test.token_client.balance(&test.alice), token_amount * 7 / 10);
+    assert_eq!(test.token_client.balance(&carol), token_amount * 3 / 10);
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance - token_amount);
+    assert_eq!(
+        test.nft_client.get_all_owners(&listing_id).unwrap().keys(),
+        vec![&test.env, test.bob.clone()]
+    );
+
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert_eq!(listing.creator, test.bob);
+}
+
+#[test]
+fn test_buyout_blocks_rentals_and_leaves_locked_shares() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let price: i128 = LISTING_PRICE;
+
+    // Carol's 200 shares are locked up until 1000
+    let listing_id: u64 = test.create_default_listing(200u32);
+    test.marketplace_client
+        .grant_reserved_shares(&listing_id, &carol, &200u32, &1_000u64, &1_000u64, &1_000u64);
+
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&price);
+    let offer_id: u64 =
+        test.marketplace_client
+            .offer_buyout(&test.bob, &listing_id, &price, &500u64);
+
+    // Nobody rents or buys the asset out from under an open offer
+    assert_eq!(
+        test.marketplace_client
+            .try_rent(&listing_id, &test.bob, &price, &100u64),
+        Err(Ok(Error::BuyoutPending.into()))
+    );
+    assert_eq!(
+        test.marketplace_client
+            .try_subscribe(&listing_id, &test.bob, &100u64, &0u64),
+        Err(Ok(Error::BuyoutPending))
+    );
+    assert!(test
+        .marketplace_client
+        .try_purchase(&listing_id, &test.bob)
+        .is_err());
+
+    // Alice's 80% drags carol along, but only with shares she could move herself
+    assert!(
+        test.marketplace_client.accept_buyout(&test.alice, &offer_id)
+            == BuyoutStatus::Completed
+    );
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 800);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 200);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &carol), 200);
+    assert_eq!(test.token_client.balance(&test.alice), token_amount * 8 / 10);
+    assert_eq!(test.token_client.balance(&carol), 0);
+    assert_eq!(
+        test.token_client.balance(&test.bob),
+        bob_balance - token_amount * 8 / 10
+    );
+
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert_eq!(listing.creator, test.bob);
+}
+
+#[test]
+fn test_buyout_accepts_only_vested_shares() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    // Carol vests 400 shares linearly from 100 to 900, with a cliff at 200
+    let listing_id: u64 = test.create_default_listing(400u32);
+    test.marketplace_client
+        .grant_reserved_shares(&listing_id, &carol, &400u32, &100u64, &200u64, &900u64);
+    let offer_id: u64 =
+        test.marketplace_client
+            .offer_buyout(&test.bob, &listing_id, &LISTING_PRICE, &1_000u64);
+
+    test.env.ledger().set_timestamp(150);
+    assert_eq!(
+        test.marketplace_client.try_accept_buyout(&carol, &offer_id),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Halfway through she sells what has vested and keeps the rest
+    test.env.ledger().set_timestamp(500);
+    assert!(
+        test.marketplace_client.accept_buyout(&carol, &offer_id) == BuyoutStatus::Open
+    );
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 200);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &carol), 200);
+    assert_eq!(
+        test.marketplace_client
+            .get_buyout(&offer_id)
+            .accepted
+            .get(carol.clone()),
+        Some(200)
+    );
+}
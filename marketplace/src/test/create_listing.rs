@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use super::MarketplaceTest;
+use super::{MarketplaceTest, LISTING_PRICE};
 use crate::types::{Listing, ListingStatus};
 use soroban_sdk::testutils::{Events, Ledger};
 use soroban_sdk::{log, Env, String};
@@ -9,7 +9,7 @@ use soroban_sdk::{log, Env, String};
 pub fn test_create_listing() {
     let test: MarketplaceTest = MarketplaceTest::setup();
     let duration: u64 = get_one_hour_duration(&test.env);
-    let price: i128 = 1_000_0_000_000; //1_000 in 7 decimals
+    let price: i128 = LISTING_PRICE;
     let reference_id = String::from_str(&test.env, "acy23bza");
     let metadata_uri = String::from_str(
         &test.env,
@@ -25,7 +25,8 @@ pub fn test_create_listing() {
         &true,
         &true,
         &1_000u32,
-        &100u32
+        &100u32,
+        &0u32
     );
 
     log!(&test.env, "{}", test.env.events().all());
@@ -0,0 +1,208 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::storage::has_persistent;
+use crate::types::{
+    Currency, DataKey, Error, Listing, ListingStatus, ProposalAction, ProposalStatus,
+};
+use common::agreement::types::AgreementStatus;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{Address, String};
+
+#[test]
+fn test_shareholder_proposals() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let price: i128 = LISTING_PRICE;
+    let voting_period: u64 = 1_000;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &test.bob, &listing_id, &400u32);
+    test.marketplace_client
+        .set_governance_config(&test.admin, &5_000u32, &5_000u32, &voting_period);
+
+    assert!(test
+        .marketplace_client
+        .try_propose(&carol, &listing_id, &ProposalAction::SetPrice(2 * price))
+        .is_err());
+
+    // Executes as soon as a majority of all shares is in favour
+    let proposal_id: u64 =
+        test.marketplace_client
+            .propose(&test.bob, &listing_id, &ProposalAction::SetPrice(2 * price));
+    assert!(test.marketplace_client.vote(&test.bob, &proposal_id, &true) == ProposalStatus::Active);
+    assert!(
+        test.marketplace_client.vote(&test.alice, &proposal_id, &true)
+            == ProposalStatus::Executed
+    );
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert_eq!(listing.price, 2 * price);
+
+    // Shares bought after the proposal don't vote, and it fails without quorum
+    let proposal_id: u64 = test.marketplace_client.propose(
+        &test.bob,
+        &listing_id,
+        &ProposalAction::ChangeOperator(carol.clone()),
+    );
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &100u32);
+    assert!(test
+        .marketplace_client
+        .try_vote(&carol, &proposal_id, &true)
+        .is_err());
+    test.marketplace_client.vote(&test.bob, &proposal_id, &true);
+    assert!(test
+        .marketplace_client
+        .try_finalize_proposal(&proposal_id)
+        .is_err());
+
+    test.env.ledger().set_timestamp(voting_period);
+    assert!(
+        test.marketplace_client.finalize_proposal(&proposal_id) == ProposalStatus::Defeated
+    );
+    assert_eq!(
+        test.marketplace_client.get_listing(&listing_id).creator,
+        test.alice
+    );
+
+    // Reserve fund payouts
+    let reserve: i128 = 10_000_000_000;
+    test.marketplace_client
+        .deposit_reserve(&test.bob, &listing_id, &reserve);
+    let proposal_id: u64 = test.marketplace_client.propose(
+        &test.alice,
+        &listing_id,
+        &ProposalAction::DistributeReserve(reserve / 2),
+    );
+    test.marketplace_client.vote(&test.alice, &proposal_id, &true);
+    test.marketplace_client.vote(&test.bob, &proposal_id, &true);
+    assert!(test.marketplace_client.get_proposal(&proposal_id).status == ProposalStatus::Executed);
+    assert_eq!(test.marketplace_client.get_reserve(&listing_id), reserve / 2);
+}
+
+#[test]
+fn test_co_owned_sale_and_reclaim_go_through_proposals() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    test.marketplace_client.set_currency(&test.admin, &Currency::USDC);
+    let listing_id: u64 = test.create_default_listing(100u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &400u32);
+
+    // The creator alone no longer manages the listing
+    assert_eq!(
+        test.marketplace_client.try_update_listing(
+            &listing_id,
+            &String::from_str(&test.env, "acy23bza"),
+            &3600u64,
+            &true,
+            &true,
+        ),
+        Err(Ok(Error::ListingCoOwned.into()))
+    );
+    assert_eq!(
+        test.marketplace_client
+            .try_purchase(&listing_id, &test.bob),
+        Err(Ok(Error::ListingCoOwned.into()))
+    );
+
+    // Renting still works, but ending it early needs the shareholders
+    let agreement_id: u64 =
+        test.marketplace_client
+            .rent(&listing_id, &test.bob, &LISTING_PRICE, &3600u64);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    assert_eq!(
+        test.marketplace_client
+            .try_reclaim_or_return(&test.alice, &listing_id),
+        Err(Ok(Error::ListingCoOwned))
+    );
+    let proposal_id: u64 =
+        test.marketplace_client
+            .propose(&test.alice, &listing_id, &ProposalAction::ReclaimAsset);
+    test.marketplace_client.vote(&test.alice, &proposal_id, &true);
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert!(listing.status == ListingStatus::Available);
+    assert!(
+        test.agreement_client.get_agreement_status(&listing.agreement_id)
+            == AgreementStatus::Completed
+    );
+    assert!(!test.nft_client.has_control(&listing_id, &test.bob));
+
+    // A passed sale lets a buyer purchase the whole asset, paying every holder by shares
+    let proposal_id: u64 = test.marketplace_client.propose(
+        &test.alice,
+        &listing_id,
+        &ProposalAction::SellAsset(LISTING_PRICE),
+    );
+    test.marketplace_client.vote(&test.alice, &proposal_id, &true);
+    let alice_balance: i128 = test.token_client.balance(&test.alice);
+    let carol_balance: i128 = test.token_client.balance(&carol);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&LISTING_PRICE);
+
+    let agreement_id: u64 = test.marketplace_client.purchase(&listing_id, &test.bob);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &false);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 1_000);
+    assert_eq!(
+        test.token_client.balance(&test.alice),
+        alice_balance + token_amount * 6 / 10
+    );
+    assert_eq!(
+        test.token_client.balance(&carol),
+        carol_balance + token_amount * 4 / 10
+    );
+    test.env.as_contract(&test.marketplace_client.address, || {
+        assert!(!has_persistent(&test.env, &DataKey::ApprovedSale(listing_id)));
+    });
+}
+
+#[test]
+fn test_passed_proposals_that_cannot_execute_fail() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let voting_period: u64 = 1_000;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &test.bob, &listing_id, &400u32);
+    test.marketplace_client
+        .set_governance_config(&test.admin, &5_000u32, &5_000u32, &voting_period);
+
+    // The deciding vote still counts when the reserve can't cover the payout
+    let reserve: i128 = 10_000_000_000;
+    test.marketplace_client
+        .deposit_reserve(&test.bob, &listing_id, &reserve);
+    let proposal_id: u64 = test.marketplace_client.propose(
+        &test.alice,
+        &listing_id,
+        &ProposalAction::DistributeReserve(2 * reserve),
+    );
+    assert!(
+        test.marketplace_client.vote(&test.alice, &proposal_id, &true) == ProposalStatus::Failed
+    );
+    assert_eq!(test.marketplace_client.get_reserve(&listing_id), reserve);
+    assert_eq!(
+        test.marketplace_client
+            .try_vote(&test.bob, &proposal_id, &true),
+        Err(Ok(Error::ProposalNotActive))
+    );
+
+    // Nothing is rented, so there's no lease to reclaim once voting ends
+    test.marketplace_client
+        .set_governance_config(&test.admin, &3_000u32, &5_000u32, &voting_period);
+    let proposal_id: u64 =
+        test.marketplace_client
+            .propose(&test.bob, &listing_id, &ProposalAction::ReclaimAsset);
+    test.marketplace_client.vote(&test.bob, &proposal_id, &true);
+    test.env.ledger().set_timestamp(voting_period);
+    assert!(
+        test.marketplace_client.finalize_proposal(&proposal_id) == ProposalStatus::Failed
+    );
+    assert!(
+        test.marketplace_client.get_listing(&listing_id).status == ListingStatus::Available
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::Error;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{Address, String};
+
+#[test]
+fn test_verification_gates_listings_and_purchases() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let verifier: Address = Address::generate(&test.env);
+    let price: i128 = LISTING_PRICE;
+
+    test.marketplace_client.set_verifier(&test.admin, &verifier, &true);
+    assert_eq!(
+        test.marketplace_client
+            .try_set_verification(&test.bob, &test.alice, &1u32, &1_000u64),
+        Err(Ok(Error::NotAVerifier))
+    );
+
+    // Verifications have to run into the future
+    assert_eq!(
+        test.marketplace_client
+            .try_set_verification(&verifier, &test.alice, &1u32, &0u64),
+        Err(Ok(Error::InvalidVerificationExpiry))
+    );
+
+    // Only verified creators list once the admin asks for it
+    test.marketplace_client.set_listing_creation_level(&test.admin, &1u32);
+    let create = || {
+        test.marketplace_client.try_create_listing(
+            &test.alice,
+            &String::from_str(&test.env, "acy23bza"),
+            &String::from_str(&test.env, "https://gearup.market/listings/acy23bza"),
+            &price,
+            &3600u64,
+            &true,
+            &true,
+            &1_000u32,
+            &0u32,
+            &2u32,
+        )
+    };
+    assert!(create().is_err());
+    test.marketplace_client
+        .set_verification(&verifier, &test.alice, &1u32, &1_000u64);
+    let listing_id: u64 = create().unwrap().unwrap();
+
+    // A high-value listing asks renters and buyers for a higher level from the start
+    assert_eq!(test.marketplace_client.get_listing_kyc_level(&listing_id), 2);
+    test.marketplace_client
+        .set_verification(&verifier, &test.bob, &1u32, &1_000u64);
+    assert!(test
+        .marketplace_client
+        .try_purchase_shares(&test.bob, &test.alice, &listing_id, &100u32)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_rent(&listing_id, &test.bob, &price, &3600u64)
+        .is_err());
+
+    test.marketplace_client
+        .set_verification(&verifier, &test.bob, &2u32, &500u64);
+    assert_eq!(test.marketplace_client.get_verification_level(&test.bob), 2);
+    test.marketplace_client
+        .purchase_shares(&test.bob, &test.alice, &listing_id, &100u32);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 100);
+    let offer_id: u64 =
+        test.marketplace_client
+            .offer_buyout(&test.bob, &listing_id, &price, &1_000u64);
+
+    // Verifications lapse
+    test.env.ledger().set_timestamp(500);
+    assert_eq!(test.marketplace_client.get_verification_level(&test.bob), 0);
+    assert!(test
+        .marketplace_client
+        .try_purchase_shares(&test.bob, &test.alice, &listing_id, &100u32)
+        .is_err());
+    assert_eq!(
+        test.marketplace_client
+            .try_subscribe(&listing_id, &test.bob, &3600u64, &0u64),
+        Err(Ok(Error::VerificationRequired))
+    );
+    assert_eq!(
+        test.marketplace_client
+            .try_offer_buyout(&test.bob, &listing_id, &price, &1_000u64),
+        Err(Ok(Error::VerificationRequired))
+    );
+    assert_eq!(
+        test.marketplace_client
+            .try_accept_buyout(&test.alice, &offer_id),
+        Err(Ok(Error::VerificationRequired))
+    );
+
+    // Dismissed verifiers can't verify anyone
+    test.marketplace_client.set_verifier(&test.admin, &verifier, &false);
+    assert!(test
+        .marketplace_client
+        .try_set_verification(&verifier, &test.bob, &2u32, &2_000u64)
+        .is_err());
+}
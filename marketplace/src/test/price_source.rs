@@ -0,0 +1,159 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::{Currency, Error, PriceSource};
+use reflector_oracle_mock::Asset as OracleAsset;
+use soroban_sdk::testutils::{Events, Ledger};
+use soroban_sdk::Symbol;
+
+#[test]
+fn test_price_source_fallback() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let amount: i128 = LISTING_PRICE;
+    let feed_rate: i128 = 1612_0000000;
+    let emergency_rate: i128 = 1700_0000000;
+
+    test.marketplace_client.set_currency(&test.admin, &Currency::USDC);
+    test.marketplace_client
+        .set_price_sources(&test.admin, &PriceSource::Feed, &PriceSource::Reflector, &600u64);
+
+    // Fresh feed price is used first
+    let quote = test.marketplace_client.get_price_quote();
+    assert_eq!(quote.source, PriceSource::Feed);
+    assert_eq!(quote.rate, feed_rate);
+
+    // Feed goes stale and Reflector has nothing, with no emergency price there's no quote
+    test.env.ledger().set_timestamp(601);
+    assert!(test.marketplace_client.try_get_price_quote().is_err());
+    assert!(test.marketplace_client.try_get_usdc_amount(&amount).is_err());
+
+    // Reflector quotes NGN in USD, the marketplace inverts it to NGN per USD
+    let ngn: OracleAsset = OracleAsset::Other(Symbol::new(&test.env, "NGN"));
+    test.reflector_client
+        .set_price(&ngn, &62_500_000_000_i128, &601u64);
+    let quote = test.marketplace_client.get_price_quote();
+    assert_eq!(quote.source, PriceSource::Reflector);
+    assert_eq!(quote.rate, 1600_0000000);
+
+    // Reflector goes stale as well
+    test.env.ledger().set_timestamp(1202);
+    assert!(test.marketplace_client.try_get_price_quote().is_err());
+
+    test.marketplace_client
+        .set_emergency_price(&test.admin, &emergency_rate, &1800u64);
+    let quote = test.marketplace_client.get_price_quote();
+    assert_eq!(quote.source, PriceSource::Emergency);
+    assert_eq!(
+        test.marketplace_client.get_usdc_amount(&amount),
+        (amount * 10_000_000) / emergency_rate
+    );
+
+    // Read-only conversions don't publish the quote they used
+    assert!(!test
+        .env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, topics, _)| topics.contains(Symbol::new(&test.env, "PriceQuoted").to_val())));
+
+    // The emergency price lapses at its expiry
+    test.env.ledger().set_timestamp(1800);
+    assert_eq!(
+        test.marketplace_client.try_get_price_quote().err(),
+        Some(Ok(Error::PriceUnavailable.into()))
+    );
+    assert_eq!(
+        test.marketplace_client.try_set_emergency_price(&test.admin, &emergency_rate, &1800u64),
+        Err(Ok(Error::InvalidEmergencyPriceExpiry.into()))
+    );
+
+    // Once the feed is refreshed it takes over again
+    test.price_feed_client
+        .update_price(&test.admin, &feed_rate);
+    assert_eq!(
+        test.marketplace_client.get_price_quote().source,
+        PriceSource::Feed
+    );
+}
+
+#[test]
+fn test_halted_feed_blocks_conversion_and_sources_are_validated() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let rate: i128 = 1612_0000000;
+
+    test.marketplace_client.set_currency(&test.admin, &Currency::USDC);
+    assert_eq!(
+        test.marketplace_client.try_set_price_sources(
+            &test.admin,
+            &PriceSource::Feed,
+            &PriceSource::Feed,
+            &600u64
+        ),
+        Err(Ok(Error::InvalidPriceSources.into()))
+    );
+    assert_eq!(
+        test.marketplace_client.try_set_price_sources(
+            &test.admin,
+            &PriceSource::Emergency,
+            &PriceSource::Reflector,
+            &600u64
+        ),
+        Err(Ok(Error::InvalidPriceSources.into()))
+    );
+
+    // A tripped breaker halts conversion even with an emergency price to fall back on
+    test.price_feed_client
+        .update_config(&test.admin, &1u64, &10_i128, &3600u64);
+    test.env.ledger().set_timestamp(10);
+    test.price_feed_client.update_price(&test.admin, &(rate * 2));
+    assert!(test.price_feed_client.is_circuit_breaker_tripped());
+    test.marketplace_client
+        .set_emergency_price(&test.admin, &rate, &3600u64);
+    assert_eq!(
+        test.marketplace_client.try_get_usdc_amount(&LISTING_PRICE),
+        Err(Ok(Error::PriceFeedHalted.into()))
+    );
+
+    // A fresh Reflector price ahead of the feed is quoted without consulting the breaker
+    let ngn: OracleAsset = OracleAsset::Other(Symbol::new(&test.env, "NGN"));
+    test.reflector_client
+        .set_price(&ngn, &62_500_000_000_i128, &10u64);
+    test.marketplace_client
+        .set_price_sources(&test.admin, &PriceSource::Reflector, &PriceSource::Feed, &600u64);
+    assert_eq!(
+        test.marketplace_client.get_price_quote().source,
+        PriceSource::Reflector
+    );
+    assert_eq!(
+        test.marketplace_client.get_usdc_amount(&LISTING_PRICE),
+        (LISTING_PRICE * 10_000_000) / 1600_0000000
+    );
+
+    // Once Reflector goes stale the feed is reached and the quote halts again
+    test.env.ledger().set_timestamp(611);
+    assert_eq!(
+        test.marketplace_client.try_get_price_quote().err(),
+        Some(Ok(Error::PriceFeedHalted.into()))
+    );
+    test.marketplace_client
+        .set_price_sources(&test.admin, &PriceSource::Feed, &PriceSource::Reflector, &3600u64);
+
+    // Once reset the feed quotes its last accepted price again
+    test.price_feed_client.reset_circuit_breaker(&test.admin);
+    assert_eq!(
+        test.marketplace_client.get_price_quote().source,
+        PriceSource::Feed
+    );
+    assert_eq!(
+        test.marketplace_client.get_usdc_amount(&LISTING_PRICE),
+        (LISTING_PRICE * 10_000_000) / rate
+    );
+
+    // Without a USDC price there's nothing to convert into
+    let usdc: OracleAsset = OracleAsset::Other(Symbol::new(&test.env, "USDC"));
+    test.reflector_client.set_price(&usdc, &0_i128, &10u64);
+    assert_eq!(
+        test.marketplace_client.try_get_usdc_amount(&LISTING_PRICE),
+        Err(Ok(Error::PriceUnavailable.into()))
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use common::nft::types::{ProvenanceEntry, ProvenanceKind, ProvenancePage};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Address;
+
+#[test]
+fn test_provenance_history() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(0u32);
+
+    test.env.ledger().set_timestamp(10);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &100u32);
+    let purchase_id: u64 =
+        test.marketplace_client
+            .purchase_shares(&test.bob, &test.alice, &listing_id, &100u32);
+
+    let rental_id: u64 = test
+        .marketplace_client
+        .rent(&listing_id, &test.bob, &price, &duration);
+    test.accept_terms(rental_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+    test.env.ledger().set_timestamp(10 + duration);
+    assert_eq!(test.marketplace_client.process_expired(&10u32), 1);
+
+    assert_eq!(test.nft_client.provenance_len(&listing_id), 5);
+
+    // Paged oldest first
+    let page: ProvenancePage = test.nft_client.provenance(&listing_id, &0u32, &2u32);
+    assert_eq!(page.entries.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+
+    // A zero limit still moves the cursor on
+    let empty: ProvenancePage = test.nft_client.provenance(&listing_id, &0u32, &0u32);
+    assert_eq!(empty.entries.len(), 1);
+    assert_eq!(empty.next_cursor, Some(1));
+
+    let mint: ProvenanceEntry = page.entries.get(0).unwrap();
+    assert_eq!(mint.kind, ProvenanceKind::Mint);
+    assert_eq!(mint.to, Some(test.alice.clone()));
+    assert_eq!(mint.shares, 1_000);
+
+    let gift: ProvenanceEntry = page.entries.get(1).unwrap();
+    assert_eq!(gift.kind, ProvenanceKind::TransferShares);
+    assert_eq!(gift.to, Some(carol));
+    assert_eq!(gift.agreement_id, 0);
+    assert_eq!(gift.timestamp, 10);
+
+    let page: ProvenancePage = test.nft_client.provenance(&listing_id, &2u32, &10u32);
+    assert_eq!(page.next_cursor, None);
+    let kinds = [
+        (ProvenanceKind::TransferShares, purchase_id),
+        (ProvenanceKind::RentalStarted, rental_id),
+        (ProvenanceKind::RentalEnded, rental_id),
+    ];
+    for (entry, (kind, agreement_id)) in page.entries.iter().zip(kinds) {
+        assert_eq!(entry.kind, kind);
+        assert_eq!(entry.agreement_id, agreement_id);
+    }
+    assert_eq!(page.entries.get(2).unwrap().timestamp, 10 + duration);
+}
@@ -1,15 +1,16 @@
 #![cfg(test)]
 extern crate std;
 
-use super::MarketplaceTest;
-use crate::types::Listing;
-use soroban_sdk::{log, Env, String};
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::{Error, Listing, ListingStatus};
+use common::agreement::types::AgreementStatus;
+use soroban_sdk::{log, BytesN, Env, String};
 
 #[test]
 fn test_purchase_or_rent() {
     let test: MarketplaceTest = MarketplaceTest::setup();
     let duration: u64 = get_one_hour_duration(&test.env);
-    let price: i128 = 1_000_0_000_000; //1_000 in 7 decimals
+    let price: i128 = LISTING_PRICE;
     let reference_id = String::from_str(&test.env, "acy23bza");
     let metadata_uri = String::from_str(
         &test.env,
@@ -25,7 +26,8 @@ fn test_purchase_or_rent() {
         &true,
         &true,
         &1_000u32,
-        &100u32
+        &100u32,
+        &0u32
     );
 
     // Verify listing
@@ -47,6 +49,55 @@ fn test_purchase_or_rent() {
     log!(&test.env, "Status {}", listing2.status);
 }
 
+#[test]
+fn test_purchase_and_confirm() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let terms_hash: BytesN<32> = BytesN::from_array(&test.env, &[1u8; 32]);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&LISTING_PRICE);
+
+    // Nothing is paid until the seller accepted the buyer's terms
+    let agreement_id: u64 =
+        test.marketplace_client
+            .request_purchase(&listing_id, &test.bob, &terms_hash);
+    assert_eq!(
+        test.marketplace_client
+            .try_purchase_and_confirm(&listing_id, &test.bob),
+        Err(Ok(Error::TermsNotAccepted.into()))
+    );
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance);
+
+    test.agreement_client
+        .sign_terms(&agreement_id, &test.alice, &terms_hash);
+    assert_eq!(
+        test.marketplace_client
+            .purchase_and_confirm(&listing_id, &test.bob),
+        agreement_id
+    );
+
+    // The buyer pays once and walks away with every share
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 1_000);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.alice), 0);
+    assert_eq!(test.token_client.balance(&test.alice), token_amount);
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance - token_amount);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id)
+            == AgreementStatus::Completed
+    );
+
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert!(listing.status == ListingStatus::Purchased);
+
+    // The sold listing can't be bought, and its holders paid, a second time
+    assert!(test
+        .marketplace_client
+        .try_purchase_and_confirm(&listing_id, &test.bob)
+        .is_err());
+    assert_eq!(test.token_client.balance(&test.alice), token_amount);
+}
+
 fn get_one_hour_duration(env: &Env) -> u64 {
     let current_ledger_time: u64 = env.ledger().timestamp(); // Get the current ledger time in seconds
     let one_hour_in_seconds: u64 = 3600; // 1 hour in seconds
@@ -0,0 +1,66 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use crate::{
+    storage::{get_persistent, has_persistent},
+    types::{DataKey, Listing, ListingStatus},
+};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Vec};
+
+#[test]
+fn test_decommission_and_redeem() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let listing_id: u64 = test.create_default_listing(0u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &test.bob, &listing_id, &250u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &250u32);
+
+    // Nothing to redeem before the listing is retired
+    assert!(test
+        .marketplace_client
+        .try_redeem_shares(&carol, &listing_id)
+        .is_err());
+
+    // The gear was sold off-platform for 2000 tokens, on top of 1000 in the reserve fund
+    test.token_client.transfer(&test.bob, &test.alice, &2_000i128);
+    test.marketplace_client
+        .deposit_reserve(&test.bob, &listing_id, &1_000i128);
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+
+    test.marketplace_client.decommission(&listing_id, &2_000i128);
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert_eq!(listing.status, ListingStatus::Decommissioned);
+    assert_eq!(test.marketplace_client.get_redemption_pool(&listing_id), 3_000);
+    assert_eq!(test.marketplace_client.get_reserve(&listing_id), 0);
+    assert!(test
+        .marketplace_client
+        .try_purchase_shares(&test.bob, &test.alice, &listing_id, &10u32)
+        .is_err());
+
+    assert_eq!(test.marketplace_client.redeem_shares(&carol, &listing_id), 750);
+    assert_eq!(test.token_client.balance(&carol), 750);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 0);
+    assert!(test
+        .marketplace_client
+        .try_redeem_shares(&carol, &listing_id)
+        .is_err());
+
+    assert_eq!(test.marketplace_client.redeem_shares(&test.bob, &listing_id), 750);
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance + 750);
+
+    // The last holder takes the rest and the listing drops out of every index
+    assert_eq!(test.marketplace_client.redeem_shares(&test.alice, &listing_id), 1_500);
+    assert!(!test.nft_client.exists(&listing_id));
+    assert_eq!(test.nft_client.tokens_of_owner(&test.alice).len(), 0);
+    assert_eq!(test.marketplace_client.get_redemption_pool(&listing_id), 0);
+    test.env.as_contract(&test.marketplace_client.address, || {
+        let listings: Vec<u64> =
+            get_persistent(&test.env, &DataKey::UserListings(test.alice.clone())).unwrap();
+        assert!(!listings.contains(listing_id));
+        assert!(!has_persistent(&test.env, &DataKey::RedemptionPool(listing_id)));
+    });
+}
@@ -0,0 +1,86 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use common::agreement::types::{Error as AgreementError, Reputation};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, BytesN};
+
+#[test]
+fn test_reviews_and_renter_requirement() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let review_hash: BytesN<32> = BytesN::from_array(&test.env, &[3u8; 32]);
+    let price: i128 = LISTING_PRICE;
+    let duration: u64 = 3600;
+
+    let listing_id: u64 = test.create_default_listing(0u32);
+
+    let agreement_id: u64 = test
+        .marketplace_client
+        .rent(&listing_id, &test.bob, &price, &duration);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &true);
+
+    // Reviews wait for the agreement to complete
+    assert_eq!(
+        test.agreement_client
+            .try_leave_review(&agreement_id, &test.bob, &5u32, &review_hash),
+        Err(Ok(AgreementError::AgreementNotCompleted))
+    );
+    test.marketplace_client
+        .reclaim_or_return(&test.alice, &listing_id);
+
+    assert_eq!(
+        test.agreement_client
+            .try_leave_review(&agreement_id, &carol, &5u32, &review_hash),
+        Err(Ok(AgreementError::AgreementNotOwnedByCaller))
+    );
+    assert_eq!(
+        test.agreement_client
+            .try_leave_review(&agreement_id, &test.bob, &6u32, &review_hash),
+        Err(Ok(AgreementError::InvalidRating))
+    );
+
+    test.agreement_client
+        .leave_review(&agreement_id, &test.bob, &5u32, &review_hash);
+    test.agreement_client
+        .leave_review(&agreement_id, &test.alice, &4u32, &review_hash);
+    assert_eq!(
+        test.agreement_client
+            .try_leave_review(&agreement_id, &test.bob, &1u32, &review_hash),
+        Err(Ok(AgreementError::AlreadyReviewed))
+    );
+
+    let alice_reputation: Reputation = test.agreement_client.get_reputation(&test.alice);
+    assert_eq!(alice_reputation.average(), 500);
+    let listing_reputation: Reputation =
+        test.agreement_client.get_listing_reputation(&listing_id);
+    assert_eq!(listing_reputation.review_count, 1);
+    let bob_reputation: Reputation = test.agreement_client.get_reputation(&test.bob);
+    assert_eq!(bob_reputation.average(), 400);
+    assert_eq!(
+        test.agreement_client
+            .get_review(&agreement_id, &test.alice)
+            .unwrap()
+            .reviewee,
+        test.bob
+    );
+
+    // A high-value listing only rents to well-reviewed renters
+    assert!(test
+        .marketplace_client
+        .try_set_renter_requirement(&listing_id, &501u32, &1u32)
+        .is_err());
+    test.marketplace_client
+        .set_renter_requirement(&listing_id, &450u32, &1u32);
+    assert!(test
+        .marketplace_client
+        .try_rent(&listing_id, &test.bob, &price, &duration)
+        .is_err());
+
+    test.marketplace_client
+        .set_renter_requirement(&listing_id, &400u32, &1u32);
+    test.marketplace_client
+        .rent(&listing_id, &test.bob, &price, &duration);
+}
@@ -0,0 +1,112 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use common::nft::types::{DataKey, TokenMetadata, TokenMetadataV1};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, String};
+
+#[test]
+fn test_share_transfers_by_holder_and_operators() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    // The holder moves their own shares
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &100u32);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 100);
+
+    // A spender is limited to its allowance
+    test.nft_client
+        .approve_shares(&test.alice, &test.bob, &listing_id, &50u32);
+    test.nft_client
+        .transfer_shares(&test.bob, &test.alice, &test.bob, &listing_id, &30u32);
+    assert_eq!(
+        test.nft_client
+            .share_allowance(&test.alice, &test.bob, &listing_id),
+        20
+    );
+    assert!(test
+        .nft_client
+        .try_transfer_shares(&test.bob, &test.alice, &test.bob, &listing_id, &30u32)
+        .is_err());
+
+    // An operator can move any amount until the approval is withdrawn
+    test.nft_client
+        .set_approval_for_all(&test.alice, &carol, &true);
+    test.nft_client
+        .transfer_shares(&carol, &test.alice, &carol, &listing_id, &400u32);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 500);
+    assert_eq!(test.nft_client.tokens_of_owner(&carol), vec![&test.env, (listing_id, 500u32)]);
+
+    test.nft_client
+        .set_approval_for_all(&test.alice, &carol, &false);
+    assert!(test
+        .nft_client
+        .try_transfer_shares(&carol, &test.alice, &carol, &listing_id, &1u32)
+        .is_err());
+}
+
+#[test]
+fn test_migrate_version_one_tokens() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let first: u64 = test.create_default_listing(100u32);
+    let second: u64 = test.create_default_listing(100u32);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &first, &100u32);
+
+    // Version 1 kept bare metadata and no owner token lists
+    let token_uri: String = String::from_str(&test.env, "https://gearup.market/listings/acy23bza");
+    test.env.as_contract(&test.nft_client.address, || {
+        let storage = test.env.storage().persistent();
+        for token_id in [first, second] {
+            let metadata: TokenMetadataV1 = TokenMetadataV1 {
+                total_shares: 1_000,
+                token_uri: token_uri.clone(),
+            };
+            storage.set(&DataKey::TokenMetadata(token_id), &metadata);
+        }
+        storage.remove(&DataKey::OwnerTokens(test.alice.clone()));
+        storage.remove(&DataKey::OwnerTokens(carol.clone()));
+    });
+    assert!(test.nft_client.tokens_of_owner(&test.alice).is_empty());
+    assert!(test.nft_client.try_total_supply(&first).is_err());
+
+    assert_eq!(test.nft_client.migrate(&test.admin, &1u32), 1);
+    assert_eq!(
+        test.nft_client.tokens_of_owner(&test.alice),
+        vec![&test.env, (first, 900u32)]
+    );
+
+    // Tokens minted meanwhile are indexed already and don't extend the migration
+    let third: u64 = test.create_default_listing(100u32);
+    assert_eq!(test.nft_client.migrate(&test.admin, &10u32), 0);
+    assert_eq!(test.nft_client.migrate(&test.admin, &10u32), 0);
+    assert_eq!(
+        test.nft_client.tokens_of_owner(&test.alice),
+        vec![&test.env, (first, 900u32), (third, 1_000u32), (second, 1_000u32)]
+    );
+    assert_eq!(
+        test.nft_client.tokens_of_owner(&carol),
+        vec![&test.env, (first, 100u32)]
+    );
+    assert!(test
+        .nft_client
+        .try_migrate(&test.bob, &10u32)
+        .is_err());
+
+    // Old metadata reads in the current layout, with the marketplace as its only editor
+    let metadata: TokenMetadata = test.nft_client.get_metadata(&second).unwrap();
+    assert_eq!(metadata.total_shares, 1_000);
+    assert_eq!(metadata.token_uri, token_uri);
+    assert_eq!(metadata.version, 1);
+    assert_eq!(metadata.creator, test.marketplace_client.address);
+    assert!(test.nft_client.burn_shares(&test.alice, &second, &100u32));
+    assert_eq!(test.nft_client.total_supply(&second), 900);
+    test.nft_client
+        .transfer(&test.alice, &test.bob, &first, &0u64);
+    assert_eq!(test.nft_client.balance_of(&first, &test.bob), 1_000);
+}
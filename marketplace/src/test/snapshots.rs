@@ -0,0 +1,34 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::Address;
+
+#[test]
+fn test_share_snapshots() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    let first: u32 = test.nft_client.snapshot(&listing_id);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &300u32);
+    let second: u32 = test.nft_client.snapshot(&listing_id);
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &100u32);
+    test.nft_client.burn_shares(&carol, &listing_id, &100u32);
+
+    assert_eq!(test.nft_client.balance_of_at(&listing_id, &test.alice, &first), 1_000);
+    assert_eq!(test.nft_client.balance_of_at(&listing_id, &carol, &first), 0);
+    assert_eq!(test.nft_client.balance_of_at(&listing_id, &test.alice, &second), 700);
+    assert_eq!(test.nft_client.balance_of_at(&listing_id, &carol, &second), 300);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 300);
+
+    assert_eq!(test.nft_client.total_supply_at(&listing_id, &second), 1_000);
+    assert_eq!(test.nft_client.total_supply(&listing_id), 900);
+    assert!(test
+        .nft_client
+        .try_balance_of_at(&listing_id, &carol, &(second + 1))
+        .is_err());
+}
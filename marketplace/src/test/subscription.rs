@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+use super::{MarketplaceTest, LISTING_PRICE};
+use crate::types::{Currency, Error};
+use common::agreement::types::{Agreement, AgreementStatus, Subscription};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Address;
+
+#[test]
+fn test_subscription_renewals_and_grace() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let price: i128 = LISTING_PRICE;
+    let period: u64 = 3600;
+    let grace_period: u64 = 600;
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&price);
+    let bob_balance: i128 = test.token_client.balance(&test.bob);
+
+    // Allowance for one renewal
+    test.token_client.approve(
+        &test.bob,
+        &test.marketplace_client.address,
+        &token_amount,
+        &1_000u32,
+    );
+
+    // A period is the listing's duration, one payment doesn't buy a longer one
+    assert_eq!(
+        test.marketplace_client
+            .try_subscribe(&listing_id, &test.bob, &(100 * period), &grace_period),
+        Err(Ok(Error::InvalidSubscriptionPeriod))
+    );
+    assert_eq!(
+        test.marketplace_client
+            .try_subscribe(&listing_id, &test.bob, &0u64, &grace_period),
+        Err(Ok(Error::InvalidSubscriptionPeriod))
+    );
+
+    let agreement_id: u64 =
+        test.marketplace_client
+            .subscribe(&listing_id, &test.bob, &period, &grace_period);
+    assert_eq!(test.token_client.balance(&test.bob), bob_balance - token_amount);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Active
+    );
+    assert_eq!(test.marketplace_client.process_renewals(&10u32), 0);
+
+    // Renewal pulled from the allowance
+    test.env.ledger().set_timestamp(period);
+    assert_eq!(test.marketplace_client.process_renewals(&10u32), 1);
+    assert_eq!(
+        test.token_client.balance(&test.bob),
+        bob_balance - 2 * token_amount
+    );
+    let agreement: Agreement = test.agreement_client.get_agreement(&agreement_id);
+    assert!(agreement.status == AgreementStatus::Active);
+    assert!(agreement.end_time == Some(2 * period));
+
+    // Allowance used up, the subscription lapses into grace
+    test.env.ledger().set_timestamp(2 * period);
+    test.marketplace_client.process_renewals(&10u32);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id) == AgreementStatus::Grace
+    );
+
+    // Left alone until the grace period is over, so it doesn't crowd out other renewals
+    test.env.ledger().set_timestamp(2 * period + 1);
+    assert_eq!(test.marketplace_client.process_renewals(&10u32), 0);
+
+    // Still unpaid after the grace period
+    test.env.ledger().set_timestamp(2 * period + grace_period + 1);
+    test.marketplace_client.process_renewals(&10u32);
+    assert!(
+        test.agreement_client.get_agreement_status(&agreement_id)
+            == AgreementStatus::Terminated
+    );
+    assert_eq!(test.agreement_client.get_due_subscriptions(&10u32).len(), 0);
+
+    let subscription: Subscription = test.agreement_client.get_subscription(&agreement_id);
+    assert_eq!(subscription.renewals, 1);
+    assert_eq!(
+        test.token_client.balance(&test.bob),
+        bob_balance - 2 * token_amount
+    );
+}
+
+#[test]
+fn test_subscription_pays_owners_converted_once() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let period: u64 = 3600;
+
+    test.marketplace_client.set_currency(&test.admin, &Currency::USDC);
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&LISTING_PRICE);
+
+    // Alice holds every share, so she's paid everything the subscriber paid
+    test.marketplace_client
+        .subscribe(&listing_id, &test.bob, &period, &0u64);
+    assert_eq!(test.token_client.balance(&test.alice), token_amount);
+    assert_eq!(
+        test.token_client.balance(&test.marketplace_client.address),
+        0
+    );
+}
+
+#[test]
+fn test_dividends_follow_shares_moved_on_the_nft() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+    let period: u64 = 3600;
+
+    test.marketplace_client.set_currency(&test.admin, &Currency::USDC);
+    let listing_id: u64 = test.create_default_listing(100u32);
+    let token_amount: i128 = test.marketplace_client.get_usdc_amount(&LISTING_PRICE);
+
+    // Half the shares change hands directly on the NFT
+    test.nft_client
+        .transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &500u32);
+
+    test.marketplace_client
+        .subscribe(&listing_id, &test.bob, &period, &0u64);
+    assert_eq!(test.token_client.balance(&test.alice), token_amount / 2);
+    assert_eq!(test.token_client.balance(&carol), token_amount / 2);
+
+    // Carol can sell what she received
+    test.marketplace_client
+        .purchase_shares(&test.bob, &carol, &listing_id, &200u32);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 300);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 200);
+}
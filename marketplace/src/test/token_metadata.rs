@@ -0,0 +1,72 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use common::nft::types::TokenMetadata;
+use soroban_sdk::testutils::Ledger;
+use soroban_sdk::{map, BytesN, String, Symbol};
+
+#[test]
+fn test_versioned_token_metadata() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let first_uri: String = String::from_str(&test.env, "https://gearup.market/listings/acy23bza");
+    let second_uri: String = String::from_str(&test.env, "ipfs://bafybeigdyrzt5sfp7udm7hu76u");
+
+    let listing_id: u64 = test.create_default_listing(0u32);
+
+    let metadata: TokenMetadata = test.nft_client.get_metadata(&listing_id).unwrap();
+    assert_eq!(metadata.creator, test.alice);
+    assert_eq!(metadata.version, 1);
+    assert_eq!(metadata.content_hash, BytesN::from_array(&test.env, &[0u8; 32]));
+
+    // Only the creator or the marketplace edits metadata
+    let content_hash: BytesN<32> = BytesN::from_array(&test.env, &[7u8; 32]);
+    assert!(test
+        .nft_client
+        .try_set_token_uri(&test.bob, &listing_id, &second_uri, &content_hash)
+        .is_err());
+
+    test.env.ledger().set_timestamp(50);
+    assert_eq!(
+        test.nft_client
+            .set_token_uri(&test.alice, &listing_id, &second_uri, &content_hash),
+        2
+    );
+    assert_eq!(test.nft_client.token_uri(&listing_id), second_uri);
+
+    let serial_number: Symbol = Symbol::new(&test.env, "serial_number");
+    let condition: Symbol = Symbol::new(&test.env, "condition");
+    test.nft_client.set_attributes(
+        &test.alice,
+        &listing_id,
+        &map![
+            &test.env,
+            (serial_number.clone(), String::from_str(&test.env, "SM58-0042")),
+            (condition.clone(), String::from_str(&test.env, "used")),
+        ],
+    );
+    // An empty value removes the attribute
+    assert_eq!(
+        test.nft_client.set_attributes(
+            &test.marketplace_client.address,
+            &listing_id,
+            &map![&test.env, (condition.clone(), String::from_str(&test.env, ""))],
+        ),
+        4
+    );
+
+    let metadata: TokenMetadata = test.nft_client.get_metadata(&listing_id).unwrap();
+    assert_eq!(metadata.content_hash, content_hash);
+    assert_eq!(metadata.updated_at, 50);
+    assert_eq!(
+        metadata.attributes.get(serial_number),
+        Some(String::from_str(&test.env, "SM58-0042"))
+    );
+    assert!(!metadata.attributes.contains_key(condition.clone()));
+
+    // Earlier versions stay readable
+    let original: TokenMetadata = test.nft_client.metadata_version(&listing_id, &1u32).unwrap();
+    assert_eq!(original.token_uri, first_uri);
+    let third: TokenMetadata = test.nft_client.metadata_version(&listing_id, &3u32).unwrap();
+    assert!(third.attributes.contains_key(condition));
+    assert!(test.nft_client.metadata_version(&listing_id, &5u32).is_none());
+}
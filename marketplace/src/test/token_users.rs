@@ -0,0 +1,65 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use common::nft::types::{Error as NftError, MAX_TOKEN_USERS};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Address;
+
+#[test]
+fn test_shared_token_users() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    // Two concurrent users with different expiries
+    test.nft_client
+        .set_user(&test.alice, &listing_id, &test.bob, &100u64);
+    test.nft_client
+        .set_user(&test.alice, &listing_id, &carol, &200u64);
+    assert_eq!(test.nft_client.user_of(&listing_id).len(), 2);
+    assert_eq!(test.nft_client.user_expires(&listing_id, &carol), 200);
+    assert!(test.nft_client.has_control(&listing_id, &test.bob));
+
+    // Only the sole owner or the marketplace can add users
+    assert!(test
+        .nft_client
+        .try_set_user(&test.bob, &listing_id, &test.bob, &300u64)
+        .is_err());
+
+    test.env.ledger().set_timestamp(100);
+    assert_eq!(test.nft_client.user_expires(&listing_id, &test.bob), 0);
+    assert!(!test.nft_client.has_control(&listing_id, &test.bob));
+    assert_eq!(test.nft_client.user_of(&listing_id).len(), 1);
+
+    // Users don't survive a change of owner
+    let agreement_id: u64 = test.marketplace_client.purchase(&listing_id, &test.bob);
+    test.accept_terms(agreement_id);
+    test.marketplace_client
+        .confirm_receipt(&test.bob, &listing_id, &false);
+    assert_eq!(test.nft_client.user_of(&listing_id).len(), 0);
+    assert_eq!(test.nft_client.tokens_of_owner(&test.alice).len(), 0);
+    assert_eq!(test.nft_client.tokens_of_owner(&test.bob).len(), 1);
+}
+
+#[test]
+fn test_rental_control_rejected_when_users_full() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let listing_id: u64 = test.create_default_listing(100u32);
+
+    for _ in 0..MAX_TOKEN_USERS {
+        test.nft_client.set_user(
+            &test.alice,
+            &listing_id,
+            &Address::generate(&test.env),
+            &1_000u64,
+        );
+    }
+
+    assert_eq!(
+        test.nft_client
+            .try_grant_temporary_control(&listing_id, &test.bob, &1_000u64, &1u64),
+        Err(Ok(NftError::TooManyUsers.into()))
+    );
+    assert_eq!(test.nft_client.user_expires(&listing_id, &test.bob), 0);
+}
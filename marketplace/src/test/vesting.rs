@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use super::MarketplaceTest;
+use crate::types::Listing;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::Address;
+
+#[test]
+fn test_vested_and_locked_reserved_shares() {
+    let test: MarketplaceTest = MarketplaceTest::setup();
+    let carol: Address = Address::generate(&test.env);
+
+    let listing_id: u64 = test.create_default_listing(400u32);
+
+    // Only reserved shares can be granted, on a well-formed schedule
+    assert!(test
+        .marketplace_client
+        .try_grant_reserved_shares(&listing_id, &carol, &500u32, &100u64, &200u64, &500u64)
+        .is_err());
+    assert!(test
+        .marketplace_client
+        .try_grant_reserved_shares(&listing_id, &carol, &200u32, &100u64, &600u64, &500u64)
+        .is_err());
+
+    // Carol vests 200 shares linearly from 100 to 500, with a cliff at 200
+    test.marketplace_client
+        .grant_reserved_shares(&listing_id, &carol, &200u32, &100u64, &200u64, &500u64);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &carol), 200);
+
+    test.env.ledger().set_timestamp(150);
+    assert_eq!(test.nft_client.vested_balance(&listing_id, &carol), 0);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &carol), 200);
+    assert!(test
+        .nft_client
+        .try_transfer_shares(&carol, &carol, &test.bob, &listing_id, &1u32)
+        .is_err());
+
+    test.env.ledger().set_timestamp(300);
+    assert_eq!(test.nft_client.vested_balance(&listing_id, &carol), 100);
+    test.nft_client
+        .transfer_shares(&carol, &carol, &test.bob, &listing_id, &100u32);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &carol), 100);
+
+    // Locked shares can't be sold through the marketplace either
+    assert!(test
+        .marketplace_client
+        .try_purchase_shares(&test.bob, &carol, &listing_id, &50u32)
+        .is_err());
+
+    // The creator locks up their own remaining reserved shares until 1000
+    test.marketplace_client
+        .grant_reserved_shares(&listing_id, &test.alice, &200u32, &1_000u64, &1_000u64, &1_000u64);
+    let listing: Listing = test.marketplace_client.get_listing(&listing_id);
+    assert_eq!(listing.reserved_shares, 0);
+    assert_eq!(listing.available_shares, 800);
+    assert_eq!(test.nft_client.vested_balance(&listing_id, &test.alice), 600);
+    assert!(test
+        .nft_client
+        .try_transfer_shares(&test.alice, &test.alice, &carol, &listing_id, &601u32)
+        .is_err());
+
+    test.env.ledger().set_timestamp(1_000);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &test.alice), 0);
+    assert_eq!(test.nft_client.locked_balance(&listing_id, &carol), 0);
+    test.marketplace_client
+        .purchase_shares(&test.bob, &carol, &listing_id, &100u32);
+    assert_eq!(test.nft_client.balance_of(&listing_id, &test.bob), 200);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, contracttype, symbol_short, Address, String, Symbol};
+use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Map, String, Symbol};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -19,7 +19,38 @@ pub enum Error {
     InsufficientSharesForPurchase = 14,
     CurrencyNotSupported = 15,
     CannotModifyShareStructure = 16,
-    InvalidSharesDistribution = 17
+    InvalidSharesDistribution = 17,
+    PriceFeedHalted = 18,
+    PriceUnavailable = 19,
+    InvalidPrice = 20,
+    AmendmentNotSettleable = 21,
+    InvalidSubscriptionPeriod = 22,
+    NotAShareholder = 23,
+    ProposalNotFound = 24,
+    ProposalNotActive = 25,
+    VotingClosed = 26,
+    AlreadyVoted = 27,
+    InvalidGovernanceConfig = 28,
+    InsufficientReserve = 29,
+    VotingNotEnded = 30,
+    BuyoutNotFound = 31,
+    BuyoutNotOpen = 32,
+    BuyoutPending = 33,
+    BuyoutExpired = 34,
+    InvalidDragAlongThreshold = 35,
+    ListingCoOwned = 36,
+    SharesLocked = 37,
+    InsufficientReservedShares = 38,
+    InvalidShareSchedule = 39,
+    ListingNotDecommissioned = 40,
+    InvalidReputationRequirement = 41,
+    ReputationTooLow = 42,
+    NotAVerifier = 43,
+    VerificationRequired = 44,
+    InvalidPriceSources = 45,
+    InvalidVerificationExpiry = 46,
+    TermsNotAccepted = 47,
+    InvalidEmergencyPriceExpiry = 48,
 }
 
 #[contracttype]
@@ -53,6 +84,7 @@ pub enum AssetType {
 pub enum PurchaseType {
     Rent = 1,
     Buy = 2,
+    Subscribe = 3,
 }
 
 #[contracttype]
@@ -63,6 +95,7 @@ pub enum ListingStatus {
     Leased = 3,
     Purchased = 4,
     Unavailable = 5,
+    Decommissioned = 6, // Terminal, holders redeem their shares for the liquidation proceeds
 }
 
 #[contracttype]
@@ -71,7 +104,19 @@ pub enum DataKey {
     Listing(u64),
     ListingCount,
     UserListings(Address),     // Listings owned by user
-    OwnershipShares(u64),      // Map of owners to their ownership percentages for a listing
+    ReserveFund(u64),          // Payment tokens held for a listing's shareholders
+    ApprovedSale(u64),         // Set by a passed `SellAsset` proposal until the asset is bought
+    Proposal(u64),
+    ProposalCount,
+    ProposalVote(u64, Address), // Whether a shareholder voted for a proposal
+    Buyout(u64),
+    BuyoutCount,
+    ListingBuyout(u64),        // Open buyout offer for a listing, at most one at a time
+    RedemptionPool(u64),       // Liquidation proceeds of a decommissioned listing left to redeem
+    RenterRequirement(u64),    // Reputation a renter needs to rent or subscribe to a listing
+    Verifier(Address),         // Appointed by the admin to verify identities
+    Identity(Address),         // Verification level of an address and when it lapses
+    ListingKycLevel(u64),      // Verification level a listing requires of renters and buyers
 }
 
 #[contracttype]
@@ -82,6 +127,126 @@ pub enum Currency {
     XLM,
 }
 
+// Where the quote currency / USD rate used by `parse_amount` comes from
+#[contracttype]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum PriceSource {
+    Feed = 1,      // Custom price feed contract
+    Reflector = 2, // Reflector oracle
+    Emergency = 3, // Admin-set price, always the last resort
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PriceSourceConfig {
+    pub primary: PriceSource,
+    pub secondary: PriceSource,
+    pub max_age: u64, // Prices older than this (in seconds) count as stale
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmergencyPrice {
+    pub rate: i128,
+    pub timestamp: u64,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Quote {
+    pub rate: i128, // Quote currency per USD, 7 decimals
+    pub timestamp: u64,
+    pub source: PriceSource,
+}
+
+// What a passed shareholder proposal does to its listing
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    SetPrice(i128),            // New listing price, in listing currency
+    SellAsset(i128),           // Put the whole asset up for sale at this price
+    ChangeOperator(Address),   // Hand the listing over to a new operator (`listing.creator`)
+    DistributeReserve(i128),   // Pay this many tokens out of the reserve fund by shares
+    ReclaimAsset,              // End the current rental on the owner's behalf
+    Decommission,              // Retire the listing, the reserve fund becomes the proceeds
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Active = 1,
+    Executed = 2,
+    Defeated = 3,
+    Failed = 4, // Passed, but its action could no longer be carried out
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Proposal {
+    pub id: u64,
+    pub listing_id: u64,
+    pub proposer: Address,
+    pub action: ProposalAction,
+    pub snapshot_id: u32, // Votes are weighted by the shares held at this NFT snapshot
+    pub total_shares: u32,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub created_at: u64,
+    pub ends_at: u64,
+    pub status: ProposalStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum BuyoutStatus {
+    Open = 1,
+    Completed = 2,
+    Cancelled = 3,
+}
+
+// Offer for 100% of a listing. The payment is held by the marketplace and accepting holders'
+// shares are held by it too until the offer completes or is cancelled.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BuyoutOffer {
+    pub id: u64,
+    pub listing_id: u64,
+    pub buyer: Address,
+    pub token_amount: i128,
+    pub total_shares: u32,
+    pub accepted: Map<Address, u32>, // Holders that accepted and the shares they handed in
+    pub accepted_shares: u32,
+    pub expires_at: u64,
+    pub status: BuyoutStatus,
+}
+
+// Thresholds in basis points of the listing's total shares (quorum) and of the votes cast
+// (approval)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GovernanceConfig {
+    pub quorum_bps: u32,
+    pub approval_bps: u32,
+    pub voting_period: u64,
+}
+
+// Minimum average rating, in hundredths of a star, and number of reviews
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationRequirement {
+    pub min_average: u32,
+    pub min_reviews: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Identity {
+    pub level: u32,
+    pub expires_at: u64,
+    pub verifier: Address,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Owner {
@@ -96,4 +261,9 @@ pub const ESCROW_CONTRACT: Symbol = symbol_short!("ESCROW_CA");
 pub const PRICE_FEED_CONTRACT: Symbol = symbol_short!("P_FEED_CA");
 pub const REFLECTOR_ORACLE: Symbol = symbol_short!("REFLECTOR");
 pub const PAYMENT_TOKEN: Symbol = symbol_short!("PAY_TOKEN");
-pub const CURRENCY: Symbol = symbol_short!("CURRENCY");
\ No newline at end of file
+pub const CURRENCY: Symbol = symbol_short!("CURRENCY");
+pub const PRICE_SOURCES: Symbol = symbol_short!("PRICE_SRC");
+pub const EMERGENCY_PRICE: Symbol = symbol_short!("EMG_PRICE");
+pub const GOVERNANCE: Symbol = symbol_short!("GOV_CFG");
+pub const DRAG_ALONG: Symbol = symbol_short!("DRAG_BPS");
+pub const LISTING_KYC: Symbol = symbol_short!("KYC_LIST"); // Level needed to create listings
\ No newline at end of file
@@ -0,0 +1,110 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::{
+    events::MarketplaceEvent,
+    storage::{get_data, get_persistent, remove_persistent, store_persistent},
+    types::{
+        BuyoutOffer, BuyoutStatus, DataKey, Error, Listing, DRAG_ALONG,
+    },
+};
+
+use super::{
+    contract_clients::get_nft_client,
+    helpers::{add_listing, get_listing_by_id, pay, remove_listing},
+};
+
+const DEFAULT_DRAG_ALONG_BPS: u32 = 7_500;
+const MAX_BPS: u32 = 10_000;
+
+// Share of all holders that must accept before the rest are made to sell as well
+pub fn get_drag_along_bps(env: &Env) -> u32 {
+    get_data(env, &DRAG_ALONG).unwrap_or(DEFAULT_DRAG_ALONG_BPS)
+}
+
+pub fn get_buyout(env: &Env, offer_id: u64) -> Result<BuyoutOffer, Error> {
+    get_persistent(env, &DataKey::Buyout(offer_id)).ok_or(Error::BuyoutNotFound)
+}
+
+pub fn store_buyout(env: &Env, offer: &BuyoutOffer) {
+    store_persistent(env, &DataKey::Buyout(offer.id), offer);
+}
+
+pub fn is_drag_along_reached(env: &Env, offer: &BuyoutOffer) -> bool {
+    offer.accepted_shares as u64 * MAX_BPS as u64
+        >= get_drag_along_bps(env) as u64 * offer.total_shares as u64
+}
+
+// Pay every holder their pro-rata part of the offer, including the ones dragged along, and
+// consolidate the token and the listing under the buyer. Holdouts are only dragged along with
+// the shares they could move themselves, unvested or locked up shares stay with them.
+pub fn complete_buyout(env: &Env, offer: &mut BuyoutOffer) {
+    let nft_client = get_nft_client(env);
+    let marketplace: Address = env.current_contract_address();
+    let listing_id: u64 = offer.listing_id;
+
+    let mut holders: Map<Address, u32> = offer.accepted.clone();
+    let mut bought: u32 = offer.accepted_shares;
+    let mut locked: u32 = 0;
+    for (owner, shares) in nft_client.get_all_owners(&listing_id).unwrap().iter() {
+        if owner == marketplace || owner == offer.buyer {
+            continue;
+        }
+        let vested: u32 = nft_client.vested_balance(&listing_id, &owner);
+        if vested > 0 {
+            nft_client.settle_shares(&owner, &marketplace, &listing_id, &vested, &0u64);
+            holders.set(owner.clone(), holders.get(owner.clone()).unwrap_or(0) + vested);
+            bought += vested;
+        }
+        locked += shares - vested;
+    }
+
+    let mut paid: i128 = 0;
+    for (holder, shares) in holders.iter() {
+        let proceeds: i128 = offer.token_amount * shares as i128 / offer.total_shares as i128;
+        pay(env, &holder, proceeds);
+        paid += proceeds;
+    }
+    // Rounding dust and the part of any shares that stayed locked go back to the buyer
+    pay(env, &offer.buyer, offer.token_amount - paid);
+
+    if locked == 0 {
+        nft_client.transfer(&marketplace, &offer.buyer, &listing_id, &0u64);
+    } else {
+        nft_client.settle_shares(&marketplace, &offer.buyer, &listing_id, &bought, &0u64);
+    }
+
+    // The listing index follows the shares
+    let mut listing: Listing = get_listing_by_id(env, listing_id);
+    for holder in holders.keys().iter().chain([listing.creator.clone()]) {
+        if nft_client.balance_of(&listing_id, &holder) == 0 {
+            remove_listing(env, listing_id, holder);
+        }
+    }
+
+    listing.creator = offer.buyer.clone();
+    add_listing(env, listing_id, offer.buyer.clone());
+    store_persistent(env, &DataKey::Listing(listing_id), &listing);
+
+    offer.status = BuyoutStatus::Completed;
+    store_buyout(env, offer);
+    remove_persistent(env, &DataKey::ListingBuyout(listing_id));
+
+    MarketplaceEvent::BuyoutCompleted(offer.id, listing_id, offer.buyer.clone()).publish(env);
+}
+
+// Hand accepted shares back and refund the buyer
+pub fn cancel_buyout(env: &Env, offer: &mut BuyoutOffer) {
+    let nft_client = get_nft_client(env);
+    let marketplace: Address = env.current_contract_address();
+
+    for (holder, shares) in offer.accepted.iter() {
+        nft_client.transfer_shares(&marketplace, &marketplace, &holder, &offer.listing_id, &shares);
+    }
+    pay(env, &offer.buyer, offer.token_amount);
+
+    offer.status = BuyoutStatus::Cancelled;
+    store_buyout(env, offer);
+    remove_persistent(env, &DataKey::ListingBuyout(offer.listing_id));
+
+    MarketplaceEvent::BuyoutCancelled(offer.id).publish(env);
+}
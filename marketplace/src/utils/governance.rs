@@ -0,0 +1,136 @@
+use common::agreement::types::{Agreement, AgreementType};
+use soroban_sdk::Env;
+
+use crate::{
+    storage::{get_data, get_persistent, store_data, store_persistent},
+    types::{
+        DataKey, Error, GovernanceConfig, Listing, ListingStatus, Proposal, ProposalAction,
+        GOVERNANCE,
+    },
+};
+
+use super::{
+    contract_clients::get_agreement_client,
+    helpers::{
+        add_listing, distribute_dividends, get_listing_by_id, remove_listing, sync_rental_control,
+    },
+    redemption::decommission_listing,
+};
+
+const DEFAULT_QUORUM_BPS: u32 = 5_000;
+const DEFAULT_APPROVAL_BPS: u32 = 5_000;
+const DEFAULT_VOTING_PERIOD: u64 = 60 * 60 * 24 * 3;
+const MAX_BPS: u32 = 10_000;
+
+pub fn get_governance_config(env: &Env) -> GovernanceConfig {
+    get_data(env, &GOVERNANCE).unwrap_or(GovernanceConfig {
+        quorum_bps: DEFAULT_QUORUM_BPS,
+        approval_bps: DEFAULT_APPROVAL_BPS,
+        voting_period: DEFAULT_VOTING_PERIOD,
+    })
+}
+
+pub fn is_valid_config(config: &GovernanceConfig) -> bool {
+    config.quorum_bps > 0
+        && config.quorum_bps <= MAX_BPS
+        && config.approval_bps < MAX_BPS
+        && config.voting_period > 0
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Result<Proposal, Error> {
+    get_persistent(env, &DataKey::Proposal(proposal_id)).ok_or(Error::ProposalNotFound)
+}
+
+pub fn store_proposal(env: &Env, proposal: &Proposal) {
+    store_persistent(env, &DataKey::Proposal(proposal.id), proposal);
+}
+
+pub fn next_proposal_id(env: &Env) -> u64 {
+    let proposal_id: u64 = get_data(env, &DataKey::ProposalCount).unwrap_or(0) + 1;
+    store_data(env, &DataKey::ProposalCount, &proposal_id);
+    proposal_id
+}
+
+fn has_quorum(config: &GovernanceConfig, proposal: &Proposal) -> bool {
+    let votes_cast: u64 = proposal.votes_for as u64 + proposal.votes_against as u64;
+    votes_cast * MAX_BPS as u64 >= config.quorum_bps as u64 * proposal.total_shares as u64
+}
+
+// Quorum reached and more than `approval_bps` of the votes cast are in favour
+pub fn has_passed(config: &GovernanceConfig, proposal: &Proposal) -> bool {
+    let votes_cast: u64 = proposal.votes_for as u64 + proposal.votes_against as u64;
+    has_quorum(config, proposal)
+        && proposal.votes_for as u64 * MAX_BPS as u64 > config.approval_bps as u64 * votes_cast
+}
+
+// Passed even if every remaining share votes against, so it can execute before voting ends
+pub fn is_decided(config: &GovernanceConfig, proposal: &Proposal) -> bool {
+    has_quorum(config, proposal)
+        && proposal.votes_for as u64 * MAX_BPS as u64
+            > config.approval_bps as u64 * proposal.total_shares as u64
+}
+
+// Checks each action before changing anything, so a failed one leaves no partial state for
+// the caller to store alongside the `Failed` status
+pub fn execute_action(env: &Env, proposal: &Proposal) -> Result<(), Error> {
+    let listing_id: u64 = proposal.listing_id;
+    let mut listing: Listing = get_listing_by_id(env, listing_id);
+
+    match proposal.action.clone() {
+        ProposalAction::SetPrice(price) => {
+            if price <= 0 {
+                return Err(Error::InvalidPrice);
+            }
+            listing.price = price;
+        }
+        ProposalAction::SellAsset(price) => {
+            if price <= 0 {
+                return Err(Error::InvalidPrice);
+            }
+            listing.price = price;
+            listing.allow_purchase = true;
+            // Lets `purchase` go ahead although the listing is co-owned
+            store_persistent(env, &DataKey::ApprovedSale(listing_id), &true);
+        }
+        ProposalAction::ChangeOperator(operator) => {
+            remove_listing(env, listing_id, listing.creator.clone());
+            add_listing(env, listing_id, operator.clone());
+            listing.creator = operator;
+        }
+        ProposalAction::DistributeReserve(amount) => {
+            let reserve: i128 = get_persistent(env, &DataKey::ReserveFund(listing_id)).unwrap_or(0);
+            if amount <= 0 || amount > reserve {
+                return Err(Error::InsufficientReserve);
+            }
+            store_persistent(env, &DataKey::ReserveFund(listing_id), &(reserve - amount));
+            distribute_dividends(env, listing_id, amount)?;
+        }
+        ProposalAction::ReclaimAsset => {
+            // Tried, so a listing without a lease to end fails the proposal instead of the call
+            let agreement_client = get_agreement_client(env);
+            let agreement: Agreement = match agreement_client
+                .try_get_agreement(&listing.agreement_id)
+            {
+                Ok(Ok(agreement)) => agreement,
+                _ => return Err(Error::AgreementNotFound),
+            };
+            if agreement.agreement_type != AgreementType::Lease {
+                return Err(Error::ListingNotAvailable);
+            }
+            if !matches!(
+                agreement_client.try_complete_agreement(&agreement.id, &agreement.owner),
+                Ok(Ok(_))
+            ) {
+                return Err(Error::AgreementNotActive);
+            }
+            sync_rental_control(env, &agreement_client.get_agreement(&agreement.id));
+            listing.status = ListingStatus::Available;
+        }
+        ProposalAction::Decommission => {
+            decommission_listing(env, &mut listing, 0)?;
+        }
+    }
+
+    store_persistent(env, &DataKey::Listing(listing_id), &listing);
+    Ok(())
+}
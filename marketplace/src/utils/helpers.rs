@@ -1,21 +1,29 @@
-use common::escrow::interface::EscrowContractClient;
+use common::{
+    agreement::types::{Agreement, AgreementStatus, AgreementType, Reputation},
+    escrow::{
+        interface::EscrowContractClient,
+        types::{Escrow, EscrowStatus},
+    },
+};
 use soroban_sdk::{panic_with_error, token, Address, Env, Map, Symbol, Vec};
 
 #[allow(unused)]
 use crate::{
     oracle::oracle::{Asset, Client as OracleClient, PriceData},
     storage::{get_persistent, store_persistent},
-    types::{DataKey, Error, Listing, ListingStatus},
+    types::{BuyoutOffer, DataKey, Error, Listing, ListingStatus, ReputationRequirement},
 };
 use crate::{
+    events::MarketplaceEvent,
     storage::get_data,
-    types::{Currency, CURRENCY, PAYMENT_TOKEN},
+    types::{Currency, Quote, CURRENCY, PAYMENT_TOKEN},
 };
 
 #[allow(unused)]
 use super::contract_clients::{
     get_agreement_client, get_escrow_client, get_feed_client, get_nft_client, get_oracle_client,
 };
+use super::price_source::get_quote;
 
 pub fn create_purchase_agreement(
     env: &Env,
@@ -38,6 +46,14 @@ pub fn create_purchase_agreement(
     agreement_id
 }
 
+// Panics unless both parties signed the agreement's current terms
+pub fn require_accepted_terms(env: &Env, agreement_id: u64) {
+    let signers: Vec<Address> = get_agreement_client(env).get_terms_signers(&agreement_id);
+    if signers.len() < 2 {
+        panic_with_error!(env, Error::TermsNotAccepted);
+    }
+}
+
 pub fn complete_agreement(
     env: &Env,
     renter_or_buyer: Address,
@@ -49,16 +65,44 @@ pub fn complete_agreement(
 
     // Transfer NFT ownership
     if is_rental {
-        // revoke temporary control
         listing.status = ListingStatus::Rented;
         agreement_client.owner_fulfilled(&listing.agreement_id);
+        sync_rental_control(env, &agreement_client.get_agreement(&listing.agreement_id));
     } else {
-        nft_client.transfer(&listing.creator, &renter_or_buyer, &listing.id);
+        nft_client.transfer(
+            &listing.creator,
+            &renter_or_buyer,
+            &listing.id,
+            &listing.agreement_id,
+        );
         listing.status = ListingStatus::Purchased;
         agreement_client.complete_agreement(&listing.agreement_id, &renter_or_buyer);
     }
 }
 
+// Match the renter's temporary control of the listing's token to the lease: held until
+// `end_time` while the lease is active, revoked once it's paused or over.
+pub fn sync_rental_control(env: &Env, agreement: &Agreement) {
+    if agreement.agreement_type != AgreementType::Lease {
+        return;
+    }
+
+    let nft_client = get_nft_client(env);
+    match (agreement.status, agreement.end_time) {
+        (AgreementStatus::Active, Some(end_time)) => nft_client.grant_temporary_control(
+            &agreement.listing_id,
+            &agreement.user,
+            &end_time,
+            &agreement.id,
+        ),
+        _ => nft_client.revoke_temporary_control(
+            &agreement.listing_id,
+            &agreement.user,
+            &agreement.id,
+        ),
+    }
+}
+
 #[allow(unused)]
 pub fn terminate_agreement(
     env: &Env,
@@ -71,13 +115,14 @@ pub fn terminate_agreement(
     true
 }
 
+// Returns the amount transferred, in token units
 #[allow(unused)]
 pub fn transfer_tokens_to_owner(
     env: &Env,
     amount: i128,
     from: &Address,
     to: &Address,
-) {
+) -> i128 {
     let token_addr: Address = get_data(env, &PAYMENT_TOKEN).unwrap();
     let token_client: token::Client<'_> = token::Client::new(&env, &token_addr);
     let balance: i128 = token_client.balance(from);
@@ -86,6 +131,66 @@ pub fn transfer_tokens_to_owner(
         panic_with_error!(&env, Error::InsufficientBalance)
     }
     token_client.transfer(from, &to, &token_amount);
+
+    token_amount
+}
+
+// Whether `renter`'s reputation from past agreements meets the listing's requirement, if any
+pub fn meets_renter_requirement(env: &Env, listing_id: u64, renter: &Address) -> bool {
+    let requirement: ReputationRequirement =
+        match get_persistent(env, &DataKey::RenterRequirement(listing_id)) {
+            Some(requirement) => requirement,
+            None => return true,
+        };
+
+    let reputation: Reputation = get_agreement_client(env).get_reputation(renter);
+    reputation.review_count >= requirement.min_reviews
+        && reputation.average() >= requirement.min_average
+}
+
+// Pays `amount` payment tokens out of the marketplace's own balance
+pub fn pay(env: &Env, to: &Address, amount: i128) {
+    if amount > 0 {
+        let token_addr: Address = get_data(env, &PAYMENT_TOKEN).unwrap();
+        token::Client::new(env, &token_addr).transfer(&env.current_contract_address(), to, &amount);
+    }
+}
+
+// Whether an active escrow holds the payment from this agreement's user to its owner
+pub fn is_agreement_escrow(escrow: &Escrow, agreement: &Agreement) -> bool {
+    matches!(escrow.status, EscrowStatus::Active)
+        && escrow.buyer == agreement.user
+        && escrow.seller == agreement.owner
+}
+
+// Moves `amount` (in listing currency) from the user into an active escrow
+pub fn top_up_escrow(env: &Env, listing_id: u64, amount: i128, from: &Address) -> i128 {
+    let token_addr: Address = get_data(env, &PAYMENT_TOKEN).unwrap();
+    let token_client: token::Client<'_> = token::Client::new(env, &token_addr);
+    let escrow_client: EscrowContractClient<'_> = get_escrow_client(env);
+
+    let token_amount: i128 = parse_amount(env, &amount);
+    if token_client.balance(from) < token_amount {
+        panic_with_error!(env, Error::InsufficientBalance)
+    }
+    token_client.transfer(from, &escrow_client.address, &token_amount);
+    escrow_client.adjust_amount(&listing_id, &token_amount);
+
+    token_amount
+}
+
+// Pulls `amount` (in listing currency) from the user's allowance to the marketplace.
+// Returns the token amount, or `None` if the allowance or balance doesn't cover it.
+pub fn collect_from_allowance(env: &Env, amount: i128, from: &Address) -> Option<i128> {
+    let token_addr: Address = get_data(env, &PAYMENT_TOKEN).unwrap();
+    let token_client: token::Client<'_> = token::Client::new(env, &token_addr);
+    let marketplace: Address = env.current_contract_address();
+
+    let token_amount: i128 = parse_amount(env, &amount);
+    match token_client.try_transfer_from(&marketplace, from, &marketplace, &token_amount) {
+        Ok(Ok(())) => Some(token_amount),
+        _ => None,
+    }
 }
 
 pub fn transfer_and_lock_tokens(
@@ -111,26 +216,67 @@ pub fn transfer_and_lock_tokens(
     escrow_client.lock_funds(&listing_id, owner, &from, &token_addr, &token_amount);
 }
 
+// Shareholders of a listing as recorded on its NFT. Shares the marketplace holds for an open
+// buyout still count for the holders that accepted it, until the buyout completes.
+pub fn get_share_holders(env: &Env, listing_id: u64) -> Map<Address, u32> {
+    let marketplace: Address = env.current_contract_address();
+    let mut holders: Map<Address, u32> = get_nft_client(env)
+        .get_all_owners(&listing_id)
+        .unwrap_or(Map::new(env));
+
+    if holders.remove(marketplace).is_some() {
+        let offer: Option<BuyoutOffer> = get_persistent::<DataKey, u64>(
+            env,
+            &DataKey::ListingBuyout(listing_id),
+        )
+        .and_then(|offer_id| get_persistent(env, &DataKey::Buyout(offer_id)));
+        if let Some(offer) = offer {
+            for (holder, shares) in offer.accepted.iter() {
+                holders.set(holder.clone(), holders.get(holder).unwrap_or(0) + shares);
+            }
+        }
+    }
+
+    holders
+}
+
+// Whether more than one holder has a say over the listing, so it answers to governance
+pub fn is_co_owned(env: &Env, listing_id: u64) -> bool {
+    get_share_holders(env, listing_id).len() > 1
+}
+
+// Splits `payment_amount` payment tokens, already converted from the listing currency, held by
+// the marketplace between the listing's shareholders
 pub fn distribute_dividends(
     env: &Env,
     listing_id: u64,
     payment_amount: i128,
 ) -> Result<(), Error> {
-    let ownership_shares: Map<Address, u32> = 
-        get_persistent(&env, &DataKey::OwnershipShares(listing_id)).unwrap_or(Map::new(&env));
-    let listing: Listing = get_listing_by_id(&env, listing_id);
-    
-    for (owner, shares) in ownership_shares.iter() {
+    let holders: Map<Address, u32> = get_share_holders(env, listing_id);
+    pay_shareholders(env, listing_id, &holders, payment_amount);
+
+    Ok(())
+}
+
+// Splits `payment_amount` payment tokens between `holders` by their shares of the token
+pub fn pay_shareholders(
+    env: &Env,
+    listing_id: u64,
+    holders: &Map<Address, u32>,
+    payment_amount: i128,
+) {
+    let supply: u32 = get_nft_client(env).total_supply(&listing_id);
+    if supply == 0 {
+        return;
+    }
+
+    for (owner, shares) in holders.iter() {
         // Calculate proportional payment
-        let owner_payment = (payment_amount * shares as i128) / listing.total_shares as i128;
-        
+        let owner_payment = (payment_amount * shares as i128) / supply as i128;
+
         // Transfer payment to owner
-        if owner_payment > 0 {
-            transfer_tokens_to_owner(&env, owner_payment,  &env.current_contract_address(), &owner);
-        }
+        pay(env, &owner, owner_payment);
     }
-    
-    Ok(())
 }
 
 pub fn get_listing_by_id(env: &Env, listing_id: u64) -> Listing {
@@ -144,38 +290,45 @@ pub fn get_listing_by_id(env: &Env, listing_id: u64) -> Listing {
 }
 
 pub fn parse_amount(env: &Env, amount: &i128) -> i128 {
+    let (usdc_amount, quote) = convert_amount(env, amount);
+    if let Some(quote) = quote {
+        MarketplaceEvent::PriceQuoted(quote.source, quote.rate).publish(env);
+    }
+
+    usdc_amount
+}
+
+// Same conversion as `parse_amount` without publishing the quote, for read-only calls
+pub fn convert_amount(env: &Env, amount: &i128) -> (i128, Option<Quote>) {
     // This converts the GUPT to USDC but relevance is questionable since GUPT is also a stable currency.
     // Maybe handle offchain?
     // Disabled for now till we agree on a best approach, so just do a dereferenced forward of amount;
 
     let curr: Currency = get_data(env, &CURRENCY).unwrap();
     if curr == Currency::NGNG {
-        *amount
+        (*amount, None)
     } else {
         let (usdc_rate, decimals) = get_usdc_price(env); // price of base in USD
-        let (usd_rate, _) = get_feed_client(&env).get_price(); // with 7 decimal, pricee of quote in USD
+        let quote: Quote = get_quote(env); // with 7 decimal, price of quote in USD
 
-        let usd_amount: i128 = (amount * 1_0_000_000) / usd_rate;
+        let usd_amount: i128 = (amount * 10_000_000) / quote.rate;
         let usdc_amount: i128 = (usd_amount * 10_i128.pow(decimals)) / usdc_rate;
 
-        usdc_amount
+        (usdc_amount, Some(quote))
     }
 }
 
-#[cfg(test)]
-pub fn get_usdc_price(_env: &Env) -> (i128, u32) {
-    (1_0_000_000, 7)
-}
-
-#[cfg(not(test))]
 pub fn get_usdc_price(env: &Env) -> (i128, u32) {
     let reflector_oracle: OracleClient<'_> = get_oracle_client(&env);
 
     let asset: Asset = Asset::Other(Symbol::new(env, "USDC"));
-    let rate: PriceData = reflector_oracle.lastprice(&asset).unwrap();
     let decimals: u32 = reflector_oracle.decimals();
 
-    (rate.price, decimals)
+    // No USDC data means no safe conversion, pricing at an assumed peg could under- or overcharge
+    match reflector_oracle.lastprice(&asset) {
+        Some(rate) if rate.price > 0 => (rate.price, decimals),
+        _ => panic_with_error!(env, Error::PriceUnavailable),
+    }
 }
 
 pub fn remove_listing(env: &Env, listing_id: u64, owner: Address) {
@@ -195,7 +348,6 @@ pub fn remove_listing(env: &Env, listing_id: u64, owner: Address) {
     }
 }
 
-#[allow(unused)]
 pub fn add_listing(env: &Env, listing_id: u64, owner: Address) {
     let mut listings: Vec<u64> = get_persistent(&env, &DataKey::UserListings(owner.clone()))
         .unwrap_or_else(|| Vec::new(&env));
@@ -0,0 +1,36 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    storage::{get_data, get_persistent},
+    types::{DataKey, Error, Identity, LISTING_KYC},
+};
+
+pub fn is_verifier(env: &Env, address: &Address) -> bool {
+    get_persistent(env, &DataKey::Verifier(address.clone())).unwrap_or(false)
+}
+
+pub fn get_identity(env: &Env, address: &Address) -> Option<Identity> {
+    get_persistent(env, &DataKey::Identity(address.clone()))
+}
+
+// Current verification level, 0 once it expires
+pub fn verification_level(env: &Env, address: &Address) -> u32 {
+    match get_identity(env, address) {
+        Some(identity) if identity.expires_at > env.ledger().timestamp() => identity.level,
+        _ => 0,
+    }
+}
+
+pub fn get_listing_creation_level(env: &Env) -> u32 {
+    get_data(env, &LISTING_KYC).unwrap_or(0)
+}
+
+pub fn get_listing_kyc_level(env: &Env, listing_id: u64) -> u32 {
+    get_persistent(env, &DataKey::ListingKycLevel(listing_id)).unwrap_or(0)
+}
+
+pub fn require_verified(env: &Env, address: &Address, level: u32) {
+    if level > 0 && verification_level(env, address) < level {
+        panic_with_error!(env, Error::VerificationRequired);
+    }
+}
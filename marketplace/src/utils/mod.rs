@@ -1,2 +1,7 @@
+pub mod buyout;
 pub mod contract_clients;
-pub mod helpers;
\ No newline at end of file
+pub mod governance;
+pub mod helpers;
+pub mod identity;
+pub mod price_source;
+pub mod redemption;
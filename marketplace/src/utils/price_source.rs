@@ -0,0 +1,103 @@
+use soroban_sdk::{panic_with_error, Env, Symbol};
+
+use crate::{
+    oracle::oracle::{Asset, PriceData},
+    storage::get_data,
+    types::{
+        EmergencyPrice, Error, PriceSource, PriceSourceConfig, Quote, EMERGENCY_PRICE,
+        PRICE_SOURCES,
+    },
+};
+
+use super::contract_clients::{get_feed_client, get_oracle_client};
+
+const DEFAULT_MAX_PRICE_AGE: u64 = 60 * 60 * 24;
+// Reflector symbol for the currency listing prices are denominated in
+const QUOTE_ASSET: &str = "NGN";
+
+pub fn get_price_source_config(env: &Env) -> PriceSourceConfig {
+    get_data(env, &PRICE_SOURCES).unwrap_or(PriceSourceConfig {
+        primary: PriceSource::Feed,
+        secondary: PriceSource::Reflector,
+        max_age: DEFAULT_MAX_PRICE_AGE,
+    })
+}
+
+// Quote currency / USD rate from the first source with a fresh price:
+// primary, then secondary, then the admin-set emergency price.
+// Reaching the feed while its circuit breaker is tripped halts the quote rather than
+// falling through, a rate it just rejected shouldn't be papered over by another source.
+pub fn get_quote(env: &Env) -> Quote {
+    let config: PriceSourceConfig = get_price_source_config(env);
+
+    for source in [config.primary, config.secondary, PriceSource::Emergency] {
+        if source == PriceSource::Feed && get_feed_client(env).is_circuit_breaker_tripped() {
+            panic_with_error!(env, Error::PriceFeedHalted);
+        }
+        if let Some(quote) = read_source(env, source, config.max_age) {
+            return quote;
+        }
+    }
+
+    panic_with_error!(env, Error::PriceUnavailable)
+}
+
+fn read_source(env: &Env, source: PriceSource, max_age: u64) -> Option<Quote> {
+    let (rate, timestamp) = match source {
+        PriceSource::Feed => read_feed(env)?,
+        PriceSource::Reflector => read_reflector(env)?,
+        PriceSource::Emergency => {
+            // Set deliberately by the admin, so it's good until its own expiry rather than max_age
+            let price: EmergencyPrice = get_data(env, &EMERGENCY_PRICE)?;
+            if env.ledger().timestamp() >= price.expires_at {
+                return None;
+            }
+            return Some(Quote {
+                rate: price.rate,
+                timestamp: price.timestamp,
+                source,
+            });
+        }
+    };
+
+    if rate <= 0 || env.ledger().timestamp().saturating_sub(timestamp) > max_age {
+        return None;
+    }
+
+    Some(Quote {
+        rate,
+        timestamp,
+        source,
+    })
+}
+
+fn read_feed(env: &Env) -> Option<(i128, u64)> {
+    match get_feed_client(env).try_get_price() {
+        Ok(Ok(price)) => Some(price),
+        _ => None,
+    }
+}
+
+fn read_reflector(env: &Env) -> Option<(i128, u64)> {
+    let reflector_oracle = get_oracle_client(env);
+    let asset: Asset = Asset::Other(Symbol::new(env, QUOTE_ASSET));
+
+    let price: PriceData = match reflector_oracle.try_lastprice(&asset) {
+        Ok(Ok(Some(price))) => price,
+        _ => return None,
+    };
+    let decimals: u32 = match reflector_oracle.try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => return None,
+    };
+
+    if price.price <= 0 {
+        return None;
+    }
+
+    // Reflector quotes the asset in USD, invert it to quote currency per USD with 7 decimals
+    Some((
+        (10_i128.pow(decimals) * 10_000_000) / price.price,
+        price.timestamp,
+    ))
+}
@@ -0,0 +1,80 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    events::MarketplaceEvent,
+    storage::{get_persistent, has_persistent, remove_persistent, store_persistent},
+    types::{DataKey, Error, Listing, ListingStatus},
+};
+
+use super::{
+    contract_clients::get_nft_client,
+    helpers::{get_listing_by_id, pay, remove_listing},
+};
+
+pub fn get_redemption_pool(env: &Env, listing_id: u64) -> i128 {
+    get_persistent(env, &DataKey::RedemptionPool(listing_id)).unwrap_or(0)
+}
+
+// Retire a listing that is sold off-platform or destroyed. `proceeds` must already be held by
+// the marketplace; whatever is left in the reserve fund is added to them. The caller stores
+// the listing.
+pub fn decommission_listing(env: &Env, listing: &mut Listing, proceeds: i128) -> Result<(), Error> {
+    if listing.status != ListingStatus::Available {
+        return Err(Error::ListingNotAvailable);
+    }
+    if has_persistent(env, &DataKey::ListingBuyout(listing.id)) {
+        return Err(Error::BuyoutPending);
+    }
+
+    let reserve: i128 = get_persistent(env, &DataKey::ReserveFund(listing.id)).unwrap_or(0);
+    remove_persistent(env, &DataKey::ReserveFund(listing.id));
+
+    let pool: i128 = proceeds + reserve;
+    store_persistent(env, &DataKey::RedemptionPool(listing.id), &pool);
+
+    listing.status = ListingStatus::Decommissioned;
+    listing.allow_purchase = false;
+    listing.allow_rent = false;
+    listing.reserved_shares = 0;
+    listing.available_shares = 0;
+
+    MarketplaceEvent::ListingDecommissioned(listing.id, pool).publish(env);
+    Ok(())
+}
+
+// Burn all of the holder's shares for their pro-rata part of what is left in the pool. The last
+// holder out takes the remainder and clears the listing from every index.
+pub fn redeem_shares(env: &Env, holder: &Address, listing_id: u64) -> Result<i128, Error> {
+    let listing: Listing = get_listing_by_id(env, listing_id);
+    if listing.status != ListingStatus::Decommissioned {
+        return Err(Error::ListingNotDecommissioned);
+    }
+
+    let nft_client = get_nft_client(env);
+    let shares: u32 = nft_client.balance_of(&listing_id, holder);
+    if shares == 0 {
+        return Err(Error::NotAShareholder);
+    }
+
+    // Measured against the live supply, so shares burned outside of redemption don't strand
+    // part of the pool
+    let supply: u32 = nft_client.total_supply(&listing_id);
+    let pool: i128 = get_redemption_pool(env, listing_id);
+    let payout: i128 = pool * shares as i128 / supply as i128;
+
+    nft_client.burn_shares(holder, &listing_id, &shares);
+
+    remove_listing(env, listing_id, holder.clone());
+
+    if shares == supply {
+        remove_listing(env, listing_id, listing.creator.clone());
+        remove_persistent(env, &DataKey::RedemptionPool(listing_id));
+    } else {
+        store_persistent(env, &DataKey::RedemptionPool(listing_id), &(pool - payout));
+    }
+
+    pay(env, holder, payout);
+
+    MarketplaceEvent::SharesRedeemed(listing_id, holder.clone(), shares, payout).publish(env);
+    Ok(payout)
+}
@@ -0,0 +1,59 @@
+use common::nft::types::{DataKey, Error};
+use soroban_sdk::{Address, Env};
+
+use crate::storage::{get_persistent, remove_persistent, store_persistent};
+
+pub fn is_approved_for_all(env: &Env, owner: &Address, operator: &Address) -> bool {
+    get_persistent(
+        env,
+        &DataKey::OperatorApproval(owner.clone(), operator.clone()),
+    )
+    .unwrap_or(false)
+}
+
+pub fn set_approval_for_all(env: &Env, owner: &Address, operator: &Address, approved: bool) {
+    let key = DataKey::OperatorApproval(owner.clone(), operator.clone());
+    if approved {
+        store_persistent(env, &key, &true);
+    } else {
+        remove_persistent(env, &key);
+    }
+}
+
+pub fn get_share_allowance(env: &Env, token_id: u64, owner: &Address, spender: &Address) -> u32 {
+    get_persistent(
+        env,
+        &DataKey::ShareAllowance(token_id, owner.clone(), spender.clone()),
+    )
+    .unwrap_or(0)
+}
+
+pub fn set_share_allowance(
+    env: &Env,
+    token_id: u64,
+    owner: &Address,
+    spender: &Address,
+    shares: u32,
+) {
+    let key = DataKey::ShareAllowance(token_id, owner.clone(), spender.clone());
+    if shares > 0 {
+        store_persistent(env, &key, &shares);
+    } else {
+        remove_persistent(env, &key);
+    }
+}
+
+pub fn spend_share_allowance(
+    env: &Env,
+    token_id: u64,
+    owner: &Address,
+    spender: &Address,
+    shares: u32,
+) -> Result<(), Error> {
+    let allowance: u32 = get_share_allowance(env, token_id, owner, spender);
+    if allowance < shares {
+        return Err(Error::InsufficientAllowance);
+    }
+    set_share_allowance(env, token_id, owner, spender, allowance - shares);
+    Ok(())
+}
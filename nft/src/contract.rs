@@ -1,16 +1,46 @@
-use soroban_sdk::{contract, contractimpl, vec, Address, BytesN, Env, Map, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, Address, BytesN, Env, Map, String, Symbol, Vec,
+};
 
 use crate::{
+    approvals::{
+        get_share_allowance, is_approved_for_all, set_approval_for_all, set_share_allowance,
+        spend_share_allowance,
+    },
     events::NFTEvent,
+    metadata::{get_metadata, get_metadata_version, require_metadata_editor, update_metadata},
+    migration::migrate_tokens,
+    owners::{add_owner_token, get_owner_tokens, remove_owner_token},
+    provenance::{new_entry, provenance_len, provenance_page, record},
+    snapshots::{
+        balance_at, checkpoint_balance, checkpoint_supply, current_snapshot, supply_at,
+        take_snapshot,
+    },
     storage::{
         get_data, get_persistent, has_data, has_persistent, remove_data, remove_persistent,
         store_data, store_persistent,
     },
+    users::{
+        bind_agreement, clear_users, get_users, is_bound_to_agreement, live_users, set_user,
+        unbind_agreement, user_expires,
+    },
     utils::require_marketplace_call,
+    vesting::{
+        get_schedule, is_valid_schedule, locked_shares, remove_schedule, require_unlocked,
+        set_schedule,
+    },
+};
+use common::impl_access_control;
+use common::access::{
+    roles::{init_admin, require_role},
+    types::Role,
 };
 use common::nft::{
     interface::NFTInterface,
-    types::{DataKey, Error, TokenMetadata, ADMIN, MARKETPLACE_CONTRACT},
+    types::{
+        DataKey, Error, ProvenanceKind, ProvenancePage, ShareSchedule, TokenMetadata, ADMIN,
+        MARKETPLACE_CONTRACT, MAX_TOKEN_ATTRIBUTES,
+    },
 };
 
 const NAME: &str = "GearUp Tokenized Asset";
@@ -19,6 +49,10 @@ const SYMBOL: &str = "GUTA";
 #[contract]
 pub struct NFTContract;
 
+impl_access_control!(NFTContract, |env: &Env| -> Address {
+    get_data(env, &ADMIN).unwrap()
+});
+
 #[contractimpl]
 impl NFTInterface for NFTContract {
     fn initialize(env: Env, admin: Address, marketplace_contract_id: Address) -> Result<(), Error> {
@@ -28,6 +62,7 @@ impl NFTInterface for NFTContract {
         }
         store_data(&env, &ADMIN, &admin);
         store_data(&env, &MARKETPLACE_CONTRACT, &marketplace_contract_id);
+        init_admin(&env, &admin);
         NFTEvent::Initialized.publish(&env);
         Ok(())
     }
@@ -36,16 +71,26 @@ impl NFTInterface for NFTContract {
         1
     }
 
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
         NFTEvent::Upgraded(Self::version()).publish(&env);
     }
 
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), Error> {
-        let admin: Address = get_data(&env, &ADMIN).unwrap();
-        admin.require_auth();
+    // After upgrading from version 1, which stored bare metadata and no owner index. Migrates
+    // up to `limit` tokens per call and returns how many are left, call again until it's 0.
+    fn migrate(env: Env, caller: Address, limit: u32) -> u64 {
+        require_role(&env, Role::Admin, &caller);
+        migrate_tokens(&env, limit)
+    }
+
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, Role::Admin, &caller);
 
         if !has_data::<Symbol>(&env, &state_key) {
             return Err(Error::StateNotAlreadySet);
@@ -69,15 +114,23 @@ impl NFTInterface for NFTContract {
     fn mint(env: Env, to: Address, token_id: u64, shares: u32, token_uri: String) -> u64 {
         require_marketplace_call(&env);
 
-        if !has_data(&env, &DataKey::TokenMetadata(token_id)) {
-            let metadata: TokenMetadata =
-                match get_persistent(&env, &DataKey::TokenMetadata(token_id)) {
-                    Some(meta) => meta,
-                    None => TokenMetadata {
-                        total_shares: shares,
-                        token_uri,
-                    },
-                };
+        if let Some(mut metadata) =
+            get_persistent::<DataKey, TokenMetadata>(&env, &DataKey::TokenMetadata(token_id))
+        {
+            // Shares minted into an existing token add to its supply
+            checkpoint_supply(&env, token_id, metadata.total_shares);
+            metadata.total_shares += shares;
+            store_persistent(&env, &DataKey::TokenMetadata(token_id), &metadata);
+        } else {
+            let metadata: TokenMetadata = TokenMetadata {
+                total_shares: shares,
+                token_uri,
+                content_hash: BytesN::from_array(&env, &[0u8; 32]),
+                attributes: Map::new(&env),
+                creator: to.clone(),
+                version: 1,
+                updated_at: env.ledger().timestamp(),
+            };
             store_persistent(&env, &DataKey::TokenMetadata(token_id), &metadata);
         }
 
@@ -85,16 +138,21 @@ impl NFTInterface for NFTContract {
             get_persistent(&env, &DataKey::TokenOwnership(token_id))
                 .unwrap_or_else(|| Map::new(&env));
         let current_shares: u32 = ownership.get(to.clone()).unwrap_or(0);
+        checkpoint_balance(&env, token_id, &to, current_shares);
         ownership.set(to.clone(), current_shares + shares);
 
         store_persistent(&env, &DataKey::TokenOwnership(token_id), &ownership);
+        add_owner_token(&env, &to, token_id);
+
+        let entry = new_entry(&env, ProvenanceKind::Mint, None, Some(to.clone()), shares, 0);
+        record(&env, token_id, &entry);
 
         NFTEvent::Mint(token_id, to).publish(&env);
 
         token_id
     }
 
-    fn transfer(env: Env, from: Address, to: Address, token_id: u64) -> bool {
+    fn transfer(env: Env, from: Address, to: Address, token_id: u64, agreement_id: u64) -> bool {
         require_marketplace_call(&env);
 
         let mut ownership: Map<Address, u32> =
@@ -103,52 +161,104 @@ impl NFTInterface for NFTContract {
         let metadata: TokenMetadata =
             get_persistent(&env, &DataKey::TokenMetadata(token_id)).unwrap();
 
+        checkpoint_balance(&env, token_id, &to, ownership.get(to.clone()).unwrap_or(0));
+
         let owners: Map<Address, u32> = ownership.clone();
-        for (owner, _) in owners.iter() {
+        for (owner, shares) in owners.iter() {
             // Remove sender's ownership
+            checkpoint_balance(&env, token_id, &owner, shares);
+            remove_owner_token(&env, &owner, token_id);
+            // The whole asset changes hands, so vesting and lockups on its shares end
+            remove_schedule(&env, token_id, &owner);
             ownership.remove(owner);
         }
 
         // Set recipient as sole owner
         ownership.set(to.clone(), metadata.total_shares);
         store_persistent(&env, &DataKey::TokenOwnership(token_id), &ownership);
+        add_owner_token(&env, &to, token_id);
 
-        // If there's a temporary control, revoke it
+        // If there's a temporary control or users, revoke them
         if has_data(&env, &DataKey::TemporaryControl(token_id)) {
             remove_data(&env, &DataKey::TemporaryControl(token_id));
         }
+        clear_users(&env, token_id);
+
+        let entry = new_entry(
+            &env,
+            ProvenanceKind::Transfer,
+            Some(from.clone()),
+            Some(to.clone()),
+            metadata.total_shares,
+            agreement_id,
+        );
+        record(&env, token_id, &entry);
 
         NFTEvent::Transfer(token_id, from, to).publish(&env);
         true
     }
 
-    // Transfer shares from one owner to another
-    fn transfer_shares(env: Env, from: Address, to: Address, token_id: u64, shares: u32) -> bool {
-        require_marketplace_call(&env);
+    // Transfer shares from one owner to another. `spender` is the holder, the marketplace, an
+    // operator approved for all of the holder's shares, or has a share allowance for this token.
+    // The spender's authority is checked before the holder's balance, and either failing is an
+    // error so a caller can't mistake a transfer that didn't happen for one that did.
+    fn transfer_shares(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        shares: u32,
+    ) -> Result<(), Error> {
+        spender.require_auth();
 
-        let mut ownership: Map<Address, u32> =
-            get_persistent(&env, &DataKey::TokenOwnership(token_id)).unwrap();
-
-        // Check if sender has enough shares
-        let from_shares: u32 = ownership.get(from.clone()).unwrap_or(0);
-        if from_shares < shares {
-            return false;
+        let marketplace: Address = get_data(&env, &MARKETPLACE_CONTRACT).unwrap();
+        if spender != from && spender != marketplace && !is_approved_for_all(&env, &from, &spender)
+        {
+            spend_share_allowance(&env, token_id, &from, &spender, shares)?;
         }
 
-        // Update sender's shares
-        if from_shares == shares {
-            ownership.remove(from.clone());
-        } else {
-            ownership.set(from.clone(), from_shares - shares);
+        if Self::balance_of(env.clone(), token_id, from.clone()) < shares {
+            return Err(Error::InsufficientShares);
         }
+        move_shares(&env, &from, &to, token_id, shares, 0);
+        Ok(())
+    }
+
+    // Move shares as part of a marketplace agreement, recorded against it in the token's history
+    fn settle_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        shares: u32,
+        agreement_id: u64,
+    ) -> bool {
+        require_marketplace_call(&env);
+        move_shares(&env, &from, &to, token_id, shares, agreement_id)
+    }
 
-        // Update recipient's shares
-        let to_shares = ownership.get(to.clone()).unwrap_or(0);
-        ownership.set(to.clone(), to_shares + shares);
+    // Let `operator` move any of `owner`'s shares, in every token
+    fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+        set_approval_for_all(&env, &owner, &operator, approved);
+        NFTEvent::ApprovalForAll(owner, operator, approved).publish(&env);
+    }
 
-        store_persistent(&env, &DataKey::TokenOwnership(token_id), &ownership);
-        NFTEvent::TransferShares(token_id, from, to, shares).publish(&env);
-        true
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        is_approved_for_all(&env, &owner, &operator)
+    }
+
+    // Let `spender` move up to `shares` of `owner`'s shares in `token_id`, replacing any
+    // previous allowance
+    fn approve_shares(env: Env, owner: Address, spender: Address, token_id: u64, shares: u32) {
+        owner.require_auth();
+        set_share_allowance(&env, token_id, &owner, &spender, shares);
+        NFTEvent::ApproveShares(token_id, owner, spender, shares).publish(&env);
+    }
+
+    fn share_allowance(env: Env, owner: Address, spender: Address, token_id: u64) -> u32 {
+        get_share_allowance(&env, token_id, &owner, &spender)
     }
 
     // Burn shares of a token (remove from circulation)
@@ -164,20 +274,27 @@ impl NFTInterface for NFTContract {
         if owner_shares < shares {
             return false;
         }
+        require_unlocked(&env, token_id, &owner, owner_shares, shares);
 
         // Update owner's shares
+        checkpoint_balance(&env, token_id, &owner, owner_shares);
         if owner_shares == shares {
             ownership.remove(owner.clone());
+            remove_owner_token(&env, &owner, token_id);
         } else {
             ownership.set(owner.clone(), owner_shares - shares);
         }
 
         store_persistent(&env, &DataKey::TokenOwnership(token_id), &ownership);
 
+        let entry = new_entry(&env, ProvenanceKind::Burn, Some(owner.clone()), None, shares, 0);
+        record(&env, token_id, &entry);
+
         // Get metadata to update total shares
         if let Some(mut metadata) =
             get_persistent::<DataKey, TokenMetadata>(&env, &DataKey::TokenMetadata(token_id))
         {
+            checkpoint_supply(&env, token_id, metadata.total_shares);
             metadata.total_shares -= shares;
 
             // If no shares left, remove the token completely
@@ -187,6 +304,7 @@ impl NFTInterface for NFTContract {
                 if has_data(&env, &DataKey::TemporaryControl(token_id)) {
                     remove_data(&env, &DataKey::TemporaryControl(token_id));
                 }
+                clear_users(&env, token_id);
             } else {
                 // Otherwise update metadata with new total
                 store_persistent(&env, &DataKey::TokenMetadata(token_id), &metadata);
@@ -222,22 +340,61 @@ impl NFTInterface for NFTContract {
         ownership.get(owner).unwrap_or(0)
     }
 
-    // Grant temporary control for rentals
-    fn grant_temporary_control(env: Env, token_id: u64, renter: Address, end_time: u64) {
+    // Grant temporary control for rentals, the renter becomes a user of the token until `end_time`
+    fn grant_temporary_control(
+        env: Env,
+        token_id: u64,
+        renter: Address,
+        end_time: u64,
+        agreement_id: u64,
+    ) {
         require_marketplace_call(&env);
-        store_data(
+        if user_expires(&env, token_id, &renter) == end_time {
+            return;
+        }
+
+        // A token already at its user limit can't take the renter on
+        if let Err(error) = set_user(&env, token_id, &renter, end_time) {
+            panic_with_error!(&env, error);
+        }
+        bind_agreement(&env, token_id, &renter, agreement_id);
+        let entry = new_entry(
             &env,
-            &DataKey::TemporaryControl(token_id),
-            &(renter, end_time),
+            ProvenanceKind::RentalStarted,
+            None,
+            Some(renter),
+            0,
+            agreement_id,
         );
+        record(&env, token_id, &entry);
     }
 
-    // Revoke temporary control
-    fn revoke_temporary_control(env: Env, token_id: u64, renter: Address) {
+    // Revoke temporary control, a no-op if `renter` doesn't hold it. A rental that already ran
+    // out still counts, so its end is recorded.
+    fn revoke_temporary_control(env: Env, token_id: u64, renter: Address, agreement_id: u64) {
         require_marketplace_call(&env);
-        let control: (Address, u64) = get_data(&env, &DataKey::TemporaryControl(token_id)).unwrap();
-        if control.0 == renter {
-            remove_data(&env, &DataKey::TemporaryControl(token_id));
+        if get_users(&env, token_id).contains_key(renter.clone()) {
+            if let Err(error) = set_user(&env, token_id, &renter, 0) {
+                panic_with_error!(&env, error);
+            }
+            unbind_agreement(&env, token_id, &renter);
+            let entry = new_entry(
+                &env,
+                ProvenanceKind::RentalEnded,
+                Some(renter.clone()),
+                None,
+                0,
+                agreement_id,
+            );
+            record(&env, token_id, &entry);
+        }
+
+        // Control granted before token users existed
+        let control: Option<(Address, u64)> = get_data(&env, &DataKey::TemporaryControl(token_id));
+        if let Some((controller, _)) = control {
+            if controller == renter {
+                remove_data(&env, &DataKey::TemporaryControl(token_id));
+            }
         }
     }
 
@@ -252,6 +409,11 @@ impl NFTInterface for NFTContract {
             }
         }
 
+        // Check if address is a user of the token
+        if user_expires(&env, token_id, &address) > 0 {
+            return true;
+        }
+
         // Check if address has temporary control
         let temp_control: Option<(Address, u64)> =
             get_data(&env, &DataKey::TemporaryControl(token_id));
@@ -264,6 +426,106 @@ impl NFTInterface for NFTContract {
         false
     }
 
+    // Let `user` use the token until `expires` without owning it, e.g. shared studio access.
+    // Several users can hold a token at once; an `expires` in the past removes the user.
+    // Called by the marketplace or the token's sole owner. A renter's use is only changed by
+    // the marketplace, through the rental agreement.
+    fn set_user(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        user: Address,
+        expires: u64,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let marketplace: Address = get_data(&env, &MARKETPLACE_CONTRACT).unwrap();
+        if caller != marketplace {
+            if !Self::is_sole_owner(env.clone(), token_id, caller) {
+                return Err(Error::NotAuthorized);
+            }
+            if is_bound_to_agreement(&env, token_id, &user) {
+                return Err(Error::UserBoundToAgreement);
+            }
+        }
+
+        set_user(&env, token_id, &user, expires)
+    }
+
+    // Current users of the token
+    fn user_of(env: Env, token_id: u64) -> Vec<Address> {
+        live_users(&env, token_id)
+    }
+
+    // When `user`'s use of the token ends, 0 if they aren't a current user
+    fn user_expires(env: Env, token_id: u64, user: Address) -> u64 {
+        user_expires(&env, token_id, &user)
+    }
+
+    // Vest or lock up `schedule.shares` of the shares `beneficiary` holds, replacing any
+    // schedule they already have on the token
+    fn set_share_schedule(
+        env: Env,
+        token_id: u64,
+        beneficiary: Address,
+        schedule: ShareSchedule,
+    ) -> Result<(), Error> {
+        require_marketplace_call(&env);
+
+        let balance: u32 = Self::balance_of(env.clone(), token_id, beneficiary.clone());
+        if !is_valid_schedule(&schedule) || schedule.shares > balance {
+            return Err(Error::InvalidSchedule);
+        }
+
+        set_schedule(&env, token_id, &beneficiary, &schedule);
+        NFTEvent::ShareScheduleSet(token_id, beneficiary, schedule.shares).publish(&env);
+        Ok(())
+    }
+
+    fn share_schedule(env: Env, token_id: u64, owner: Address) -> Option<ShareSchedule> {
+        get_schedule(&env, token_id, &owner)
+    }
+
+    // Shares the owner can move now
+    fn vested_balance(env: Env, token_id: u64, owner: Address) -> u32 {
+        let balance: u32 = Self::balance_of(env.clone(), token_id, owner.clone());
+        balance - locked_shares(&env, token_id, &owner, balance)
+    }
+
+    // Shares the owner holds that are still unvested or locked up
+    fn locked_balance(env: Env, token_id: u64, owner: Address) -> u32 {
+        let balance: u32 = Self::balance_of(env.clone(), token_id, owner.clone());
+        locked_shares(&env, token_id, &owner, balance)
+    }
+
+    // Record the current balances and supply of a token, e.g. before a dividend or a vote.
+    // Returns the snapshot ID to read them back with.
+    fn snapshot(env: Env, token_id: u64) -> u32 {
+        require_marketplace_call(&env);
+        let snapshot_id: u32 = take_snapshot(&env, token_id);
+        NFTEvent::Snapshot(token_id, snapshot_id).publish(&env);
+        snapshot_id
+    }
+
+    fn current_snapshot(env: Env, token_id: u64) -> u32 {
+        current_snapshot(&env, token_id)
+    }
+
+    fn balance_of_at(
+        env: Env,
+        token_id: u64,
+        owner: Address,
+        snapshot_id: u32,
+    ) -> Result<u32, Error> {
+        let current: u32 = Self::balance_of(env.clone(), token_id, owner.clone());
+        balance_at(&env, token_id, &owner, snapshot_id, current)
+    }
+
+    fn total_supply_at(env: Env, token_id: u64, snapshot_id: u32) -> Result<u32, Error> {
+        let current: u32 = Self::total_supply(env.clone(), token_id);
+        supply_at(&env, token_id, snapshot_id, current)
+    }
+
     // Get token balance (shares) for an address
     fn balance_of(env: Env, token_id: u64, owner: Address) -> u32 {
         let ownership: Option<Map<Address, u32>> =
@@ -298,29 +560,70 @@ impl NFTInterface for NFTContract {
         metadata.token_uri
     }
 
-    fn set_token_uri(env: Env, token_id: u64, uri: String) {
-        // Only admin or marketplace can call this
-        require_marketplace_call(&env);
+    // Point the token at new off-chain metadata, with the hash of its JSON. Returns the new
+    // metadata version; the previous one stays readable through `metadata_version`.
+    fn set_token_uri(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        uri: String,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        let mut metadata: TokenMetadata =
+            get_metadata(&env, token_id).ok_or(Error::ListingNotFound)?;
+        require_metadata_editor(&env, &caller, &metadata)?;
+
+        metadata.token_uri = uri;
+        metadata.content_hash = content_hash;
+        Ok(update_metadata(&env, token_id, metadata))
+    }
 
-        if let Some(mut metadata) = env
-            .storage()
-            .instance()
-            .get::<DataKey, TokenMetadata>(&DataKey::TokenMetadata(token_id))
-        {
-            metadata.token_uri = uri;
-            // Save updated metadata
-            store_persistent(&env, &DataKey::TokenMetadata(token_id), &metadata);
+    // Set on-chain attributes such as serial_number, condition or category. An empty value
+    // removes the attribute. Returns the new metadata version.
+    fn set_attributes(
+        env: Env,
+        caller: Address,
+        token_id: u64,
+        attributes: Map<Symbol, String>,
+    ) -> Result<u32, Error> {
+        let mut metadata: TokenMetadata =
+            get_metadata(&env, token_id).ok_or(Error::ListingNotFound)?;
+        require_metadata_editor(&env, &caller, &metadata)?;
+
+        for (key, value) in attributes.iter() {
+            if value.is_empty() {
+                metadata.attributes.remove(key);
+            } else {
+                metadata.attributes.set(key, value);
+            }
+        }
+        if metadata.attributes.len() > MAX_TOKEN_ATTRIBUTES {
+            return Err(Error::TooManyAttributes);
         }
+
+        Ok(update_metadata(&env, token_id, metadata))
+    }
+
+    fn metadata_version(env: Env, token_id: u64, version: u32) -> Option<TokenMetadata> {
+        get_metadata_version(&env, token_id, version)
     }
 
     fn exists(env: Env, token_id: u64) -> bool {
         has_persistent(&env, &DataKey::TokenMetadata(token_id))
     }
 
+    // Every mint, transfer, share movement, burn, rental and metadata update of a token, oldest
+    // first. Kept after the token is burned.
+    fn provenance(env: Env, token_id: u64, cursor: u32, limit: u32) -> ProvenancePage {
+        provenance_page(&env, token_id, cursor, limit)
+    }
+
+    fn provenance_len(env: Env, token_id: u64) -> u32 {
+        provenance_len(&env, token_id)
+    }
+
     fn get_metadata(env: Env, token_id: u64) -> Option<TokenMetadata> {
-        env.storage()
-            .instance()
-            .get(&DataKey::TokenMetadata(token_id))
+        get_metadata(&env, token_id)
     }
 
     fn owners_of(env: Env, token_id: u64) -> Vec<Address> {
@@ -333,14 +636,7 @@ impl NFTInterface for NFTContract {
     fn tokens_of_owner(env: Env, owner: Address) -> Vec<(u64, u32)> {
         let mut result = Vec::new(&env);
 
-        // Get current listing count from marketplace to know max token ID
-        let marketplace: Address = get_data(&env, &MARKETPLACE_CONTRACT).unwrap();
-        let args = vec![&env];
-        let listing_count: u64 =
-            env.invoke_contract(&marketplace, &Symbol::new(&env, "get_listing_count"), args);
-
-        // Check each token ID up to listing count
-        for token_id in 1..=listing_count {
+        for token_id in get_owner_tokens(&env, &owner).iter() {
             if let Some(ownership) = get_persistent::<DataKey, Map<Address, u32>>(
                 &env,
                 &DataKey::TokenOwnership(token_id),
@@ -354,3 +650,53 @@ impl NFTInterface for NFTContract {
         result
     }
 }
+
+// Move shares between owners once the caller is authorized, false if `from` holds too few
+fn move_shares(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    token_id: u64,
+    shares: u32,
+    agreement_id: u64,
+) -> bool {
+    let mut ownership: Map<Address, u32> =
+        get_persistent(env, &DataKey::TokenOwnership(token_id)).unwrap();
+
+    // Check if sender has enough shares
+    let from_shares: u32 = ownership.get(from.clone()).unwrap_or(0);
+    if from_shares < shares {
+        return false;
+    }
+    require_unlocked(env, token_id, from, from_shares, shares);
+
+    // Update sender's shares
+    checkpoint_balance(env, token_id, from, from_shares);
+    if from_shares == shares {
+        ownership.remove(from.clone());
+        remove_owner_token(env, from, token_id);
+    } else {
+        ownership.set(from.clone(), from_shares - shares);
+    }
+
+    // Update recipient's shares
+    let to_shares = ownership.get(to.clone()).unwrap_or(0);
+    checkpoint_balance(env, token_id, to, to_shares);
+    ownership.set(to.clone(), to_shares + shares);
+    add_owner_token(env, to, token_id);
+
+    store_persistent(env, &DataKey::TokenOwnership(token_id), &ownership);
+
+    let entry = new_entry(
+        env,
+        ProvenanceKind::TransferShares,
+        Some(from.clone()),
+        Some(to.clone()),
+        shares,
+        agreement_id,
+    );
+    record(env, token_id, &entry);
+
+    NFTEvent::TransferShares(token_id, from.clone(), to.clone(), shares).publish(env);
+    true
+}
@@ -6,6 +6,12 @@ pub enum NFTEvent {
     Mint(u64, Address),
     Transfer(u64, Address, Address),
     TransferShares(u64, Address, Address, u32),
+    UpdateUser(u64, Address, u64),
+    ApprovalForAll(Address, Address, bool),
+    ApproveShares(u64, Address, Address, u32),
+    Snapshot(u64, u32),
+    ShareScheduleSet(u64, Address, u32),
+    MetadataUpdate(u64, u32),
 }
 
 impl NFTEvent {
@@ -16,6 +22,12 @@ impl NFTEvent {
             NFTEvent::Mint(..) => stringify!(Mint),
             NFTEvent::Transfer(..) => stringify!(Transfer),
             NFTEvent::TransferShares(..) => stringify!(TransferSharesTransferShares),
+            NFTEvent::UpdateUser(..) => stringify!(UpdateUser),
+            NFTEvent::ApprovalForAll(..) => stringify!(ApprovalForAll),
+            NFTEvent::ApproveShares(..) => stringify!(ApproveShares),
+            NFTEvent::Snapshot(..) => stringify!(Snapshot),
+            NFTEvent::ShareScheduleSet(..) => stringify!(ShareScheduleSet),
+            NFTEvent::MetadataUpdate(..) => stringify!(MetadataUpdate),
         }
     }
 
@@ -42,6 +54,35 @@ impl NFTEvent {
                 v.push_back(to.into_val(env));
                 v.push_back(shares.into_val(env));
             }
+            NFTEvent::UpdateUser(token_id, user, expires) => {
+                v.push_back(token_id.into_val(env));
+                v.push_back(user.into_val(env));
+                v.push_back(expires.into_val(env));
+            }
+            NFTEvent::ApprovalForAll(owner, operator, approved) => {
+                v.push_back(owner.into_val(env));
+                v.push_back(operator.into_val(env));
+                v.push_back(approved.into_val(env));
+            }
+            NFTEvent::ApproveShares(token_id, owner, spender, shares) => {
+                v.push_back(token_id.into_val(env));
+                v.push_back(owner.into_val(env));
+                v.push_back(spender.into_val(env));
+                v.push_back(shares.into_val(env));
+            }
+            NFTEvent::Snapshot(token_id, snapshot_id) => {
+                v.push_back(token_id.into_val(env));
+                v.push_back(snapshot_id.into_val(env));
+            }
+            NFTEvent::ShareScheduleSet(token_id, beneficiary, shares) => {
+                v.push_back(token_id.into_val(env));
+                v.push_back(beneficiary.into_val(env));
+                v.push_back(shares.into_val(env));
+            }
+            NFTEvent::MetadataUpdate(token_id, version) => {
+                v.push_back(token_id.into_val(env));
+                v.push_back(version.into_val(env));
+            }
         }
 
         env.events().publish((self.name(),), v)
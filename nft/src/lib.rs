@@ -1,5 +1,16 @@
 #![no_std]
 mod utils;
+mod approvals;
 mod events;
+mod metadata;
+mod migration;
+mod owners;
+mod provenance;
+mod snapshots;
+mod users;
+mod vesting;
 pub mod contract;
-pub mod storage;
\ No newline at end of file
+pub mod storage;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,58 @@
+use common::nft::types::{
+    DataKey, Error, ProvenanceKind, TokenMetadata, MARKETPLACE_CONTRACT,
+};
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    events::NFTEvent,
+    provenance::{new_entry, record},
+    storage::{get_data, get_persistent, store_persistent},
+};
+
+pub fn get_metadata(env: &Env, token_id: u64) -> Option<TokenMetadata> {
+    get_persistent(env, &DataKey::TokenMetadata(token_id))
+}
+
+// Metadata as it was at `version`, including the current one
+pub fn get_metadata_version(env: &Env, token_id: u64, version: u32) -> Option<TokenMetadata> {
+    match get_metadata(env, token_id) {
+        Some(metadata) if metadata.version == version => Some(metadata),
+        _ => get_persistent(env, &DataKey::MetadataVersion(token_id, version)),
+    }
+}
+
+// The token's creator or the marketplace may edit its metadata
+pub fn require_metadata_editor(
+    env: &Env,
+    caller: &Address,
+    metadata: &TokenMetadata,
+) -> Result<(), Error> {
+    caller.require_auth();
+
+    let marketplace: Address = get_data(env, &MARKETPLACE_CONTRACT).unwrap();
+    if *caller != marketplace && *caller != metadata.creator {
+        return Err(Error::NotAuthorized);
+    }
+    Ok(())
+}
+
+// Keep the current version for the record and store `metadata` as the next one
+pub fn update_metadata(env: &Env, token_id: u64, mut metadata: TokenMetadata) -> u32 {
+    let current: TokenMetadata = get_metadata(env, token_id).unwrap();
+    store_persistent(
+        env,
+        &DataKey::MetadataVersion(token_id, current.version),
+        &current,
+    );
+
+    metadata.version = current.version + 1;
+    metadata.updated_at = env.ledger().timestamp();
+    store_persistent(env, &DataKey::TokenMetadata(token_id), &metadata);
+
+    let mut entry = new_entry(env, ProvenanceKind::MetadataUpdated, None, None, 0, 0);
+    entry.token_uri = Some(metadata.token_uri.clone());
+    record(env, token_id, &entry);
+
+    NFTEvent::MetadataUpdate(token_id, metadata.version).publish(env);
+    metadata.version
+}
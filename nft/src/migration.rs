@@ -0,0 +1,61 @@
+use common::nft::types::{DataKey, TokenMetadata, TokenMetadataV1, MARKETPLACE_CONTRACT};
+use soroban_sdk::{vec, Address, BytesN, Env, Map, Symbol, TryFromVal, Val};
+
+use crate::owners::add_owner_token;
+use crate::storage::{get_data, get_persistent, store_data, store_persistent};
+
+// Rewrite metadata stored by version 1, which only kept the shares and URI. The creator
+// isn't known here, so the marketplace is left as the only editor.
+fn upgrade_metadata(env: &Env, token_id: u64) {
+    let key = DataKey::TokenMetadata(token_id);
+    let Some(value) = get_persistent::<DataKey, Val>(env, &key) else {
+        return;
+    };
+    if let Ok(old) = TokenMetadataV1::try_from_val(env, &value) {
+        let marketplace: Address = get_data(env, &MARKETPLACE_CONTRACT).unwrap();
+        let metadata: TokenMetadata = TokenMetadata {
+            total_shares: old.total_shares,
+            token_uri: old.token_uri,
+            content_hash: BytesN::from_array(env, &[0u8; 32]),
+            attributes: Map::new(env),
+            creator: marketplace,
+            version: 1,
+            updated_at: env.ledger().timestamp(),
+        };
+        store_persistent(env, &key, &metadata);
+    }
+}
+
+// Bring up to `limit` tokens minted before the upgrade up to date: metadata is rewritten to
+// the current layout and owners are added to the owner index. Token IDs are listing IDs, so
+// the marketplace's listing count at the first call bounds them; later tokens were minted in
+// the current layout. Returns how many tokens are left to look at.
+pub fn migrate_tokens(env: &Env, limit: u32) -> u64 {
+    let count: u64 = get_data(env, &DataKey::TokensToMigrate).unwrap_or_else(|| {
+        let marketplace: Address = get_data(env, &MARKETPLACE_CONTRACT).unwrap();
+        let count: u64 = env.invoke_contract(
+            &marketplace,
+            &Symbol::new(env, "get_listing_count"),
+            vec![env],
+        );
+        store_data(env, &DataKey::TokensToMigrate, &count);
+        count
+    });
+    let mut migrated: u64 = get_data(env, &DataKey::TokensMigrated).unwrap_or(0);
+    let last: u64 = count.min(migrated + limit as u64);
+
+    while migrated < last {
+        migrated += 1;
+        upgrade_metadata(env, migrated);
+        if let Some(ownership) =
+            get_persistent::<DataKey, Map<Address, u32>>(env, &DataKey::TokenOwnership(migrated))
+        {
+            for owner in ownership.keys().iter() {
+                add_owner_token(env, &owner, migrated);
+            }
+        }
+    }
+
+    store_data(env, &DataKey::TokensMigrated, &migrated);
+    count - migrated
+}
@@ -0,0 +1,29 @@
+use common::nft::types::DataKey;
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{get_persistent, remove_persistent, store_persistent};
+
+// Token IDs `owner` holds shares in
+pub fn get_owner_tokens(env: &Env, owner: &Address) -> Vec<u64> {
+    get_persistent(env, &DataKey::OwnerTokens(owner.clone())).unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn add_owner_token(env: &Env, owner: &Address, token_id: u64) {
+    let mut tokens: Vec<u64> = get_owner_tokens(env, owner);
+    if !tokens.contains(token_id) {
+        tokens.push_back(token_id);
+        store_persistent(env, &DataKey::OwnerTokens(owner.clone()), &tokens);
+    }
+}
+
+pub fn remove_owner_token(env: &Env, owner: &Address, token_id: u64) {
+    let mut tokens: Vec<u64> = get_owner_tokens(env, owner);
+    if let Some(index) = tokens.first_index_of(token_id) {
+        tokens.remove(index);
+        if tokens.is_empty() {
+            remove_persistent(env, &DataKey::OwnerTokens(owner.clone()));
+        } else {
+            store_persistent(env, &DataKey::OwnerTokens(owner.clone()), &tokens);
+        }
+    }
+}
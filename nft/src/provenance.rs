@@ -0,0 +1,56 @@
+use common::nft::types::{
+    DataKey, ProvenanceEntry, ProvenanceKind, ProvenancePage, MAX_PAGE_SIZE,
+};
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{get_persistent, store_persistent};
+
+pub fn provenance_len(env: &Env, token_id: u64) -> u32 {
+    get_persistent(env, &DataKey::ProvenanceCount(token_id)).unwrap_or(0)
+}
+
+pub fn new_entry(
+    env: &Env,
+    kind: ProvenanceKind,
+    from: Option<Address>,
+    to: Option<Address>,
+    shares: u32,
+    agreement_id: u64,
+) -> ProvenanceEntry {
+    ProvenanceEntry {
+        kind,
+        from,
+        to,
+        shares,
+        agreement_id,
+        token_uri: None,
+        timestamp: env.ledger().timestamp(),
+    }
+}
+
+// Entries are only ever appended, each under its own key so history never has to be rewritten
+pub fn record(env: &Env, token_id: u64, entry: &ProvenanceEntry) {
+    let index: u32 = provenance_len(env, token_id);
+    store_persistent(env, &DataKey::Provenance(token_id, index), entry);
+    store_persistent(env, &DataKey::ProvenanceCount(token_id), &(index + 1));
+}
+
+// Oldest first, starting at `cursor`
+pub fn provenance_page(env: &Env, token_id: u64, cursor: u32, limit: u32) -> ProvenancePage {
+    // An empty page would hand back the same cursor forever
+    let limit: u32 = limit.clamp(1, MAX_PAGE_SIZE);
+    let len: u32 = provenance_len(env, token_id);
+    let end: u32 = len.min(cursor.saturating_add(limit));
+
+    let mut entries: Vec<ProvenanceEntry> = Vec::new(env);
+    for index in cursor..end {
+        if let Some(entry) = get_persistent(env, &DataKey::Provenance(token_id, index)) {
+            entries.push_back(entry);
+        }
+    }
+
+    ProvenancePage {
+        entries,
+        next_cursor: if end < len { Some(end) } else { None },
+    }
+}
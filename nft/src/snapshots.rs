@@ -0,0 +1,106 @@
+use common::nft::types::{DataKey, Error};
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{get_persistent, store_persistent};
+
+// Checkpoints are (snapshot ID, value) pairs holding the value a balance or supply had when
+// that snapshot was taken. One is written lazily, just before the first change after a
+// snapshot, so untouched balances cost nothing.
+
+pub fn current_snapshot(env: &Env, token_id: u64) -> u32 {
+    get_persistent(env, &DataKey::SnapshotCount(token_id)).unwrap_or(0)
+}
+
+pub fn take_snapshot(env: &Env, token_id: u64) -> u32 {
+    let snapshot_id: u32 = current_snapshot(env, token_id) + 1;
+    store_persistent(env, &DataKey::SnapshotCount(token_id), &snapshot_id);
+    snapshot_id
+}
+
+// Call before `owner`'s balance changes, with the balance it's about to lose
+pub fn checkpoint_balance(env: &Env, token_id: u64, owner: &Address, balance: u32) {
+    checkpoint(
+        env,
+        token_id,
+        &DataKey::BalanceCheckpoints(token_id, owner.clone()),
+        balance,
+    );
+}
+
+// Call before the total supply changes, with the supply it's about to lose
+pub fn checkpoint_supply(env: &Env, token_id: u64, supply: u32) {
+    checkpoint(env, token_id, &DataKey::SupplyCheckpoints(token_id), supply);
+}
+
+pub fn balance_at(
+    env: &Env,
+    token_id: u64,
+    owner: &Address,
+    snapshot_id: u32,
+    current: u32,
+) -> Result<u32, Error> {
+    value_at(
+        env,
+        token_id,
+        &DataKey::BalanceCheckpoints(token_id, owner.clone()),
+        snapshot_id,
+        current,
+    )
+}
+
+pub fn supply_at(env: &Env, token_id: u64, snapshot_id: u32, current: u32) -> Result<u32, Error> {
+    value_at(
+        env,
+        token_id,
+        &DataKey::SupplyCheckpoints(token_id),
+        snapshot_id,
+        current,
+    )
+}
+
+fn checkpoint(env: &Env, token_id: u64, key: &DataKey, value: u32) {
+    let snapshot_id: u32 = current_snapshot(env, token_id);
+    if snapshot_id == 0 {
+        return;
+    }
+
+    let mut checkpoints: Vec<(u32, u32)> =
+        get_persistent(env, key).unwrap_or_else(|| Vec::new(env));
+    let is_recorded: bool = checkpoints
+        .last()
+        .is_some_and(|(last_id, _)| last_id >= snapshot_id);
+    if !is_recorded {
+        checkpoints.push_back((snapshot_id, value));
+        store_persistent(env, key, &checkpoints);
+    }
+}
+
+// The first checkpoint at or after `snapshot_id` holds the value at that snapshot. With none,
+// the value hasn't changed since and `current` is still right.
+fn value_at(
+    env: &Env,
+    token_id: u64,
+    key: &DataKey,
+    snapshot_id: u32,
+    current: u32,
+) -> Result<u32, Error> {
+    if snapshot_id == 0 || snapshot_id > current_snapshot(env, token_id) {
+        return Err(Error::InvalidSnapshot);
+    }
+
+    let checkpoints: Vec<(u32, u32)> = get_persistent(env, key).unwrap_or_else(|| Vec::new(env));
+    let (mut low, mut high) = (0u32, checkpoints.len());
+    while low < high {
+        let mid: u32 = (low + high) / 2;
+        if checkpoints.get(mid).unwrap().0 < snapshot_id {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok(match checkpoints.get(low) {
+        Some((_, value)) => value,
+        None => current,
+    })
+}
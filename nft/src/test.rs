@@ -0,0 +1,251 @@
+extern crate std;
+
+use super::contract::NFTContract;
+use common::nft::{
+    interface::NFTContractClient,
+    types::{Error, ShareSchedule},
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
+
+const TOKEN_ID: u64 = 1;
+const TOTAL_SHARES: u32 = 1000;
+
+struct NFTTest<'a> {
+    env: Env,
+    owner: Address,
+    client: NFTContractClient<'a>,
+}
+
+impl NFTTest<'_> {
+    // One token wholly held by `owner`. The marketplace is a plain address here, mocked auths
+    // stand in for its calls.
+    fn setup() -> Self {
+        let env: Env = Env::default();
+        env.mock_all_auths();
+
+        let admin: Address = Address::generate(&env);
+        let marketplace: Address = Address::generate(&env);
+        let contract_id: Address = env.register(NFTContract, ());
+        let client = NFTContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &marketplace);
+
+        let owner: Address = Address::generate(&env);
+        client.mint(
+            &owner,
+            &TOKEN_ID,
+            &TOTAL_SHARES,
+            &String::from_str(&env, "ipfs://token"),
+        );
+
+        NFTTest { env, owner, client }
+    }
+}
+
+#[test]
+fn test_renter_use_changed_only_through_agreement() {
+    let test = NFTTest::setup();
+    let renter: Address = Address::generate(&test.env);
+    let guest: Address = Address::generate(&test.env);
+
+    test.client
+        .grant_temporary_control(&TOKEN_ID, &renter, &1_000u64, &7u64);
+    test.client.set_user(&test.owner, &TOKEN_ID, &guest, &500u64);
+    assert_eq!(test.client.user_of(&TOKEN_ID).len(), 2);
+
+    // The owner manages their own users but can't cut a rental short
+    assert_eq!(
+        test.client
+            .try_set_user(&test.owner, &TOKEN_ID, &renter, &0u64),
+        Err(Ok(Error::UserBoundToAgreement))
+    );
+    assert_eq!(
+        test.client
+            .try_set_user(&test.owner, &TOKEN_ID, &renter, &2_000u64),
+        Err(Ok(Error::UserBoundToAgreement))
+    );
+    test.client.set_user(&test.owner, &TOKEN_ID, &guest, &0u64);
+    assert_eq!(test.client.user_expires(&TOKEN_ID, &renter), 1_000);
+
+    // Once the rental runs out the address is just another user
+    test.env.ledger().set_timestamp(1_000);
+    test.client.set_user(&test.owner, &TOKEN_ID, &renter, &2_000u64);
+    assert_eq!(test.client.user_expires(&TOKEN_ID, &renter), 2_000);
+
+    // Revoking through the marketplace releases the record
+    test.client
+        .grant_temporary_control(&TOKEN_ID, &renter, &3_000u64, &8u64);
+    test.client
+        .revoke_temporary_control(&TOKEN_ID, &renter, &8u64);
+    assert!(!test.client.has_control(&TOKEN_ID, &renter));
+    test.client.set_user(&test.owner, &TOKEN_ID, &renter, &2_500u64);
+    assert_eq!(test.client.user_expires(&TOKEN_ID, &renter), 2_500);
+}
+
+#[test]
+fn test_allowance_spent_by_transfers() {
+    let test = NFTTest::setup();
+    let spender: Address = Address::generate(&test.env);
+    let operator: Address = Address::generate(&test.env);
+    let buyer: Address = Address::generate(&test.env);
+
+    test.client
+        .approve_shares(&test.owner, &spender, &TOKEN_ID, &300u32);
+    test.client
+        .transfer_shares(&spender, &test.owner, &buyer, &TOKEN_ID, &200u32);
+    assert_eq!(
+        test.client.share_allowance(&test.owner, &spender, &TOKEN_ID),
+        100
+    );
+    assert_eq!(
+        test.client
+            .try_transfer_shares(&spender, &test.owner, &buyer, &TOKEN_ID, &200u32),
+        Err(Ok(Error::InsufficientAllowance))
+    );
+
+    // A spender without authority is refused before the balance is looked at, one with it is
+    // told the holder is short
+    assert_eq!(
+        test.client
+            .try_transfer_shares(&spender, &test.owner, &buyer, &TOKEN_ID, &5_000u32),
+        Err(Ok(Error::InsufficientAllowance))
+    );
+    test.client
+        .approve_shares(&test.owner, &spender, &TOKEN_ID, &5_000u32);
+    assert_eq!(
+        test.client
+            .try_transfer_shares(&spender, &test.owner, &buyer, &TOKEN_ID, &5_000u32),
+        Err(Ok(Error::InsufficientShares))
+    );
+    assert_eq!(
+        test.client.share_allowance(&test.owner, &spender, &TOKEN_ID),
+        5_000
+    );
+
+    // An operator moves shares without an allowance until the approval is withdrawn
+    test.client
+        .set_approval_for_all(&test.owner, &operator, &true);
+    test.client
+        .transfer_shares(&operator, &test.owner, &buyer, &TOKEN_ID, &300u32);
+    assert_eq!(test.client.balance_of(&TOKEN_ID, &buyer), 500);
+    test.client
+        .set_approval_for_all(&test.owner, &operator, &false);
+    assert!(!test.client.is_approved_for_all(&test.owner, &operator));
+    assert_eq!(
+        test.client
+            .try_transfer_shares(&operator, &test.owner, &buyer, &TOKEN_ID, &1u32),
+        Err(Ok(Error::InsufficientAllowance))
+    );
+}
+
+#[test]
+fn test_snapshot_keeps_balances_and_supply() {
+    let test = NFTTest::setup();
+    let holder: Address = Address::generate(&test.env);
+    assert_eq!(
+        test.client.try_balance_of_at(&TOKEN_ID, &test.owner, &1u32),
+        Err(Ok(Error::InvalidSnapshot))
+    );
+
+    let first: u32 = test.client.snapshot(&TOKEN_ID);
+    test.client
+        .transfer_shares(&test.owner, &test.owner, &holder, &TOKEN_ID, &400u32);
+    test.client.burn_shares(&holder, &TOKEN_ID, &100u32);
+    let second: u32 = test.client.snapshot(&TOKEN_ID);
+    test.client
+        .transfer_shares(&holder, &holder, &test.owner, &TOKEN_ID, &50u32);
+
+    assert_eq!(test.client.current_snapshot(&TOKEN_ID), 2);
+    assert_eq!(
+        test.client.balance_of_at(&TOKEN_ID, &test.owner, &first),
+        TOTAL_SHARES
+    );
+    assert_eq!(test.client.balance_of_at(&TOKEN_ID, &holder, &first), 0);
+    assert_eq!(test.client.total_supply_at(&TOKEN_ID, &first), TOTAL_SHARES);
+    assert_eq!(test.client.balance_of_at(&TOKEN_ID, &holder, &second), 300);
+    assert_eq!(test.client.total_supply_at(&TOKEN_ID, &second), 900);
+
+    // Changes after the last snapshot leave it as it was
+    assert_eq!(test.client.balance_of(&TOKEN_ID, &holder), 250);
+    assert_eq!(
+        test.client.balance_of_at(&TOKEN_ID, &test.owner, &second),
+        600
+    );
+    assert_eq!(
+        test.client.try_total_supply_at(&TOKEN_ID, &3u32),
+        Err(Ok(Error::InvalidSnapshot))
+    );
+}
+
+#[test]
+fn test_vesting_locks_unreleased_shares() {
+    let test = NFTTest::setup();
+    let buyer: Address = Address::generate(&test.env);
+    let schedule = |shares: u32, cliff: u64| ShareSchedule {
+        shares,
+        start: 100,
+        cliff,
+        end: 1_100,
+    };
+
+    assert_eq!(
+        test.client
+            .try_set_share_schedule(&TOKEN_ID, &test.owner, &schedule(TOTAL_SHARES + 1, 200)),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        test.client
+            .try_set_share_schedule(&TOKEN_ID, &test.owner, &schedule(600, 50)),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    test.client
+        .set_share_schedule(&TOKEN_ID, &test.owner, &schedule(600, 200));
+
+    // Nothing is released before the cliff
+    test.env.ledger().set_timestamp(150);
+    assert_eq!(test.client.locked_balance(&TOKEN_ID, &test.owner), 600);
+    assert_eq!(
+        test.client
+            .try_transfer_shares(&test.owner, &test.owner, &buyer, &TOKEN_ID, &500u32),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Then linearly from the start, halfway through half the schedule is free
+    test.env.ledger().set_timestamp(600);
+    assert_eq!(test.client.vested_balance(&TOKEN_ID, &test.owner), 700);
+    test.client
+        .transfer_shares(&test.owner, &test.owner, &buyer, &TOKEN_ID, &700u32);
+    assert_eq!(test.client.vested_balance(&TOKEN_ID, &test.owner), 0);
+    assert_eq!(
+        test.client
+            .try_burn_shares(&test.owner, &TOKEN_ID, &1u32),
+        Err(Ok(Error::SharesLocked.into()))
+    );
+
+    test.env.ledger().set_timestamp(1_100);
+    assert_eq!(test.client.locked_balance(&TOKEN_ID, &test.owner), 0);
+    assert!(test.client.burn_shares(&test.owner, &TOKEN_ID, &300u32));
+}
+
+#[test]
+fn test_mint_into_existing_token_adds_supply() {
+    let test = NFTTest::setup();
+    let uri: String = String::from_str(&test.env, "ipfs://token");
+
+    // Listings created without shares get them minted later
+    test.client.mint(&test.owner, &2u64, &0u32, &uri);
+    assert_eq!(test.client.total_supply(&2u64), 0);
+    test.client.mint(&test.owner, &2u64, &500u32, &uri);
+    assert_eq!(test.client.total_supply(&2u64), 500);
+    assert_eq!(test.client.balance_of(&2u64, &test.owner), 500);
+
+    let snapshot_id: u32 = test.client.snapshot(&TOKEN_ID);
+    test.client.mint(&test.owner, &TOKEN_ID, &200u32, &uri);
+    assert_eq!(test.client.total_supply(&TOKEN_ID), TOTAL_SHARES + 200);
+    assert_eq!(
+        test.client.total_supply_at(&TOKEN_ID, &snapshot_id),
+        TOTAL_SHARES
+    );
+}
@@ -0,0 +1,89 @@
+use common::nft::types::{DataKey, Error, MAX_TOKEN_USERS};
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::{
+    events::NFTEvent,
+    storage::{get_persistent, remove_persistent, store_persistent},
+};
+
+// Users of a token and when their use expires, including ones that already expired
+pub fn get_users(env: &Env, token_id: u64) -> Map<Address, u64> {
+    get_persistent(env, &DataKey::TokenUsers(token_id)).unwrap_or_else(|| Map::new(env))
+}
+
+pub fn live_users(env: &Env, token_id: u64) -> Vec<Address> {
+    let current_time = env.ledger().timestamp();
+    let mut users: Vec<Address> = Vec::new(env);
+    for (user, expires) in get_users(env, token_id).iter() {
+        if expires > current_time {
+            users.push_back(user);
+        }
+    }
+    users
+}
+
+pub fn user_expires(env: &Env, token_id: u64, user: &Address) -> u64 {
+    match get_users(env, token_id).get(user.clone()) {
+        Some(expires) if expires > env.ledger().timestamp() => expires,
+        _ => 0,
+    }
+}
+
+// Add, update or (with an `expires` in the past) remove a user. Expired users are pruned on
+// every write so they don't count towards `MAX_TOKEN_USERS`.
+pub fn set_user(env: &Env, token_id: u64, user: &Address, expires: u64) -> Result<(), Error> {
+    let current_time = env.ledger().timestamp();
+    let mut users: Map<Address, u64> = Map::new(env);
+    for (existing, existing_expires) in get_users(env, token_id).iter() {
+        if existing_expires > current_time && existing != *user {
+            users.set(existing, existing_expires);
+        }
+    }
+
+    if expires > current_time {
+        if users.len() >= MAX_TOKEN_USERS {
+            return Err(Error::TooManyUsers);
+        }
+        users.set(user.clone(), expires);
+    }
+
+    if users.is_empty() {
+        remove_persistent(env, &DataKey::TokenUsers(token_id));
+    } else {
+        store_persistent(env, &DataKey::TokenUsers(token_id), &users);
+    }
+
+    let expires: u64 = if expires > current_time { expires } else { 0 };
+    NFTEvent::UpdateUser(token_id, user.clone(), expires).publish(env);
+    Ok(())
+}
+
+// Drop every user, e.g. when the token changes hands
+pub fn clear_users(env: &Env, token_id: u64) {
+    let users: Map<Address, u64> = get_users(env, token_id);
+    if users.is_empty() {
+        return;
+    }
+
+    remove_persistent(env, &DataKey::TokenUsers(token_id));
+    for user in users.keys() {
+        unbind_agreement(env, token_id, &user);
+        NFTEvent::UpdateUser(token_id, user, 0).publish(env);
+    }
+}
+
+// Record that `user` holds the token under `agreement_id`, so only the marketplace changes it
+pub fn bind_agreement(env: &Env, token_id: u64, user: &Address, agreement_id: u64) {
+    store_persistent(env, &DataKey::UserAgreement(token_id, user.clone()), &agreement_id);
+}
+
+pub fn unbind_agreement(env: &Env, token_id: u64, user: &Address) {
+    remove_persistent(env, &DataKey::UserAgreement(token_id, user.clone()));
+}
+
+// Whether `user` currently uses the token under an agreement
+pub fn is_bound_to_agreement(env: &Env, token_id: u64, user: &Address) -> bool {
+    user_expires(env, token_id, user) > 0
+        && get_persistent::<DataKey, u64>(env, &DataKey::UserAgreement(token_id, user.clone()))
+            .is_some()
+}
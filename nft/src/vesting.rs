@@ -0,0 +1,53 @@
+use common::nft::types::{DataKey, Error, ShareSchedule};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::storage::{get_persistent, remove_persistent, store_persistent};
+
+pub fn get_schedule(env: &Env, token_id: u64, owner: &Address) -> Option<ShareSchedule> {
+    get_persistent(env, &DataKey::ShareSchedule(token_id, owner.clone()))
+}
+
+pub fn is_valid_schedule(schedule: &ShareSchedule) -> bool {
+    schedule.shares > 0 && schedule.start <= schedule.cliff && schedule.cliff <= schedule.end
+}
+
+pub fn set_schedule(env: &Env, token_id: u64, owner: &Address, schedule: &ShareSchedule) {
+    store_persistent(env, &DataKey::ShareSchedule(token_id, owner.clone()), schedule);
+}
+
+pub fn remove_schedule(env: &Env, token_id: u64, owner: &Address) {
+    remove_persistent(env, &DataKey::ShareSchedule(token_id, owner.clone()));
+}
+
+// Shares of the schedule released by `time`
+pub fn released_shares(schedule: &ShareSchedule, time: u64) -> u32 {
+    if time < schedule.cliff {
+        return 0;
+    }
+    if time >= schedule.end {
+        return schedule.shares;
+    }
+
+    let elapsed: u64 = time - schedule.start;
+    let duration: u64 = schedule.end - schedule.start;
+    ((schedule.shares as u64 * elapsed) / duration) as u32
+}
+
+// Shares an owner holds that can't move yet, never more than they hold
+pub fn locked_shares(env: &Env, token_id: u64, owner: &Address, balance: u32) -> u32 {
+    match get_schedule(env, token_id, owner) {
+        Some(schedule) => {
+            let locked: u32 =
+                schedule.shares - released_shares(&schedule, env.ledger().timestamp());
+            locked.min(balance)
+        }
+        None => 0,
+    }
+}
+
+// Panics if moving `shares` out of `balance` would touch locked shares
+pub fn require_unlocked(env: &Env, token_id: u64, owner: &Address, balance: u32, shares: u32) {
+    if balance - locked_shares(env, token_id, owner, balance) < shares {
+        panic_with_error!(env, Error::SharesLocked);
+    }
+}
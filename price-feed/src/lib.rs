@@ -1,17 +1,31 @@
 #![no_std]
+use common::impl_access_control;
+use common::access::{
+    roles::{has_role, init_admin, require_role},
+    types::Role,
+};
 use common::pricefeed::{
     interface::PriceOracleContractTrait,
-    types::{OracleConfig, OracleError, PriceData},
+    types::{
+        CircuitBreaker, OracleConfig, OracleError, PriceData, ReportOutcome, SignedPriceReport,
+    },
 };
-use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, BytesN, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, BytesN, Env, Symbol, Vec};
 
-const ADMIN: Symbol = symbol_short!("ADMIN");
 const PRICE: Symbol = symbol_short!("PRICE");
 const CONFIG: Symbol = symbol_short!("CONFIG");
+const BREAKER: Symbol = symbol_short!("BREAKER");
+const REPORTERS: Symbol = symbol_short!("REPORTERS");
+const MIN_REPORTS: Symbol = symbol_short!("MIN_RPTS");
+const REPORT_NONCE: Symbol = symbol_short!("RPT_NONCE");
 
 #[contract]
 pub struct PriceOracleContract;
 
+impl_access_control!(PriceOracleContract, |env: &Env| -> Address {
+    env.storage().instance().get::<_, OracleConfig>(&CONFIG).unwrap().admin
+});
+
 #[contractimpl]
 impl PriceOracleContractTrait for PriceOracleContract {
     fn initialize(
@@ -22,7 +36,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
         min_update_interval: u64,
         max_price_change: i128,
     ) -> Result<(), OracleError> {
-        if env.storage().instance().has(&ADMIN) {
+        if env.storage().instance().has(&CONFIG) {
             return Err(OracleError::AlreadyInitialized);
         }
 
@@ -41,6 +55,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
 
         env.storage().instance().set(&CONFIG, &config);
         env.storage().instance().set(&PRICE, &price_data);
+        init_admin(&env, &admin);
 
         env.events().publish(("initialized", admin), initial_rate);
 
@@ -51,15 +66,18 @@ impl PriceOracleContractTrait for PriceOracleContract {
         1
     }
 
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
-    fn update_state(env: Env, state_key: Symbol, state_value: Address) -> Result<(), OracleError> {
-        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        admin.require_auth();
+    fn update_state(
+        env: Env,
+        caller: Address,
+        state_key: Symbol,
+        state_value: Address,
+    ) -> Result<(), OracleError> {
+        require_role(&env, Role::Admin, &caller);
 
         if !env.storage().instance().has::<Symbol>(&state_key) {
             return Err(OracleError::StateNotAlreadySet);
@@ -85,38 +103,21 @@ impl PriceOracleContractTrait for PriceOracleContract {
             return Err(OracleError::Unauthorized);
         }
 
-        let current_price: PriceData = env
-            .storage()
-            .instance()
-            .get(&PRICE)
-            .ok_or(OracleError::NotInitialized)?;
-
-        let current_time = env.ledger().timestamp();
-        if current_time - current_price.timestamp < config.min_update_interval {
-            return Err(OracleError::MinimumUpdateInterval);
-        }
-
-        let price_change = ((new_rate - current_price.rate) * 100) / current_price.rate;
-        if price_change.abs() > config.max_price_change {
-            return Err(OracleError::MaximumUpdateInterval);
+        if apply_price(&env, &config, new_rate, env.ledger().timestamp())? {
+            env.events().publish(("price_updated", updater), new_rate);
+        } else {
+            env.events()
+                .publish(("circuit_breaker_tripped", updater), new_rate);
         }
 
-        let new_price_data = PriceData {
-            rate: new_rate,
-            timestamp: current_time,
-            valid_period: current_price.valid_period,
-        };
-
-        env.storage()
-            .instance()
-            .set(&PRICE, &new_price_data);
-
-        env.events().publish(("price_updated", updater), new_rate);
-
         Ok(())
     }
 
     fn get_price(env: Env) -> Result<(i128, u64), OracleError> {
+        if get_breaker(&env).tripped {
+            return Err(OracleError::CircuitBreakerTripped);
+        }
+
         let price_data: PriceData = env
             .storage()
             .instance()
@@ -125,8 +126,8 @@ impl PriceOracleContractTrait for PriceOracleContract {
         Ok((price_data.rate, price_data.timestamp))
     }
 
-    fn add_updater(env: Env, admin: Address, new_updater: Address) -> Result<(), OracleError> {
-        admin.require_auth();
+    fn add_updater(env: Env, caller: Address, new_updater: Address) -> Result<(), OracleError> {
+        caller.require_auth();
 
         let mut config: OracleConfig = env
             .storage()
@@ -134,7 +135,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
             .get(&CONFIG)
             .ok_or(OracleError::NotInitialized)?;
 
-        if admin != config.admin {
+        if !has_role(&env, Role::OracleManager, &caller) {
             return Err(OracleError::Unauthorized);
         }
 
@@ -144,14 +145,14 @@ impl PriceOracleContractTrait for PriceOracleContract {
                 .instance()
                 .set(&CONFIG, &config);
 
-            env.events().publish(("updater_added", admin), new_updater);
+            env.events().publish(("updater_added", caller), new_updater);
         }
 
         Ok(())
     }
 
-    fn remove_updater(env: Env, admin: Address, updater: Address) -> Result<(), OracleError> {
-        admin.require_auth();
+    fn remove_updater(env: Env, caller: Address, updater: Address) -> Result<(), OracleError> {
+        caller.require_auth();
 
         let mut config: OracleConfig = env
             .storage()
@@ -159,7 +160,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
             .get(&CONFIG)
             .ok_or(OracleError::NotInitialized)?;
 
-        if admin != config.admin {
+        if !has_role(&env, Role::OracleManager, &caller) {
             return Err(OracleError::Unauthorized);
         }
 
@@ -169,7 +170,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
                 .instance()
                 .set(&CONFIG, &config);
 
-            env.events().publish(("updater_removed", admin), updater);
+            env.events().publish(("updater_removed", caller), updater);
         }
 
         Ok(())
@@ -177,12 +178,12 @@ impl PriceOracleContractTrait for PriceOracleContract {
 
     fn update_config(
         env: Env,
-        admin: Address,
+        caller: Address,
         min_update_interval: u64,
         max_price_change: i128,
         valid_period: u64,
     ) -> Result<(), OracleError> {
-        admin.require_auth();
+        caller.require_auth();
 
         let mut config: OracleConfig = env
             .storage()
@@ -190,7 +191,7 @@ impl PriceOracleContractTrait for PriceOracleContract {
             .get(&CONFIG)
             .ok_or(OracleError::NotInitialized)?;
 
-        if admin != config.admin {
+        if !has_role(&env, Role::OracleManager, &caller) {
             return Err(OracleError::Unauthorized);
         }
 
@@ -213,8 +214,318 @@ impl PriceOracleContractTrait for PriceOracleContract {
             .set(&PRICE, &price_data);
 
         env.events()
-            .publish(("config_updated", admin), min_update_interval);
+            .publish(("config_updated", caller), min_update_interval);
+
+        Ok(())
+    }
+
+    fn trip_circuit_breaker(env: Env, caller: Address) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::Pauser, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        let breaker = CircuitBreaker {
+            tripped: true,
+            tripped_at: env.ledger().timestamp(),
+            rejected_rate: 0,
+        };
+        env.storage().instance().set(&BREAKER, &breaker);
+
+        env.events()
+            .publish(("circuit_breaker_tripped", caller), 0_i128);
+
+        Ok(())
+    }
+
+    // Clear the breaker after review, keeping the last accepted price
+    fn reset_circuit_breaker(env: Env, caller: Address) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::OracleManager, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        env.storage().instance().remove(&BREAKER);
+        env.events()
+            .publish(("circuit_breaker_reset", caller), env.ledger().timestamp());
+
+        Ok(())
+    }
+
+    // Set the price directly, bypassing the interval and change limits, and clear the breaker
+    fn force_set_price(env: Env, caller: Address, new_rate: i128) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::OracleManager, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        if new_rate <= 0 {
+            return Err(OracleError::InvalidPrice);
+        }
+
+        let current_price: PriceData = env
+            .storage()
+            .instance()
+            .get(&PRICE)
+            .ok_or(OracleError::NotInitialized)?;
+
+        let new_price_data = PriceData {
+            rate: new_rate,
+            timestamp: env.ledger().timestamp(),
+            valid_period: current_price.valid_period,
+        };
+
+        env.storage()
+            .instance()
+            .set(&PRICE, &new_price_data);
+        env.storage().instance().remove(&BREAKER);
+
+        env.events()
+            .publish(("price_overridden", caller), (current_price.rate, new_rate));
+
+        Ok(())
+    }
+
+    fn get_circuit_breaker(env: Env) -> CircuitBreaker {
+        get_breaker(&env)
+    }
+
+    fn is_circuit_breaker_tripped(env: Env) -> bool {
+        get_breaker(&env).tripped
+    }
+
+    fn add_reporter(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::OracleManager, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        let mut reporters: Vec<BytesN<32>> = Self::get_reporters(env.clone());
+        if !reporters.contains(&public_key) {
+            reporters.push_back(public_key.clone());
+            env.storage().instance().set(&REPORTERS, &reporters);
+
+            env.events().publish(("reporter_added", caller), public_key);
+        }
+
+        Ok(())
+    }
+
+    fn remove_reporter(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::OracleManager, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        let mut reporters: Vec<BytesN<32>> = Self::get_reporters(env.clone());
+        if let Some(index) = reporters.first_index_of(&public_key) {
+            // Too few reporters left to ever reach the minimum would halt the feed
+            let min_reports: Option<u32> = env.storage().instance().get(&MIN_REPORTS);
+            if min_reports.is_some_and(|min_reports| reporters.len() - 1 < min_reports) {
+                return Err(OracleError::InsufficientReports);
+            }
+            reporters.remove(index);
+            env.storage().instance().set(&REPORTERS, &reporters);
+
+            env.events().publish(("reporter_removed", caller), public_key);
+        }
+
+        Ok(())
+    }
+
+    fn get_reporters(env: Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&REPORTERS)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Minimum number of distinct reporters a bundle must carry to move the price, at most the
+    // number of registered reporters
+    fn set_min_reports(env: Env, caller: Address, min_reports: u32) -> Result<(), OracleError> {
+        caller.require_auth();
+
+        if !has_role(&env, Role::OracleManager, &caller) {
+            return Err(OracleError::Unauthorized);
+        }
+
+        if min_reports == 0 || min_reports > Self::get_reporters(env.clone()).len() {
+            return Err(OracleError::InsufficientReports);
+        }
+
+        env.storage().instance().set(&MIN_REPORTS, &min_reports);
+        env.events()
+            .publish(("min_reports_updated", caller), min_reports);
 
         Ok(())
     }
+
+    fn get_report_nonce(env: Env) -> u64 {
+        env.storage().instance().get(&REPORT_NONCE).unwrap_or(0)
+    }
+
+    // Permissionless: anyone may relay a bundle, the reporter signatures are what authorize it.
+    // The bundle nonce must be greater than the last accepted one and the median rate is applied
+    // with the same interval, change and circuit breaker rules as `update_price`. A rate that
+    // trips the breaker comes back as `Rejected`.
+    fn submit_reports(
+        env: Env,
+        timestamp: u64,
+        nonce: u64,
+        reports: Vec<SignedPriceReport>,
+    ) -> Result<ReportOutcome, OracleError> {
+        let config: OracleConfig = env
+            .storage()
+            .instance()
+            .get(&CONFIG)
+            .ok_or(OracleError::NotInitialized)?;
+
+        if nonce <= Self::get_report_nonce(env.clone()) {
+            return Err(OracleError::InvalidNonce);
+        }
+
+        let current_price: PriceData = env
+            .storage()
+            .instance()
+            .get(&PRICE)
+            .ok_or(OracleError::NotInitialized)?;
+
+        let current_time = env.ledger().timestamp();
+        if timestamp > current_time
+            || timestamp <= current_price.timestamp
+            || current_time - timestamp > current_price.valid_period
+        {
+            return Err(OracleError::InvalidTimestamp);
+        }
+
+        let min_reports: u32 = env.storage().instance().get(&MIN_REPORTS).unwrap_or(1);
+        if reports.len() < min_reports {
+            return Err(OracleError::InsufficientReports);
+        }
+
+        let reporters: Vec<BytesN<32>> = Self::get_reporters(env.clone());
+        let feed: Address = env.current_contract_address();
+        let mut signers: Vec<BytesN<32>> = Vec::new(&env);
+        let mut rates: Vec<i128> = Vec::new(&env);
+
+        for report in reports.iter() {
+            if !reporters.contains(&report.public_key) {
+                return Err(OracleError::UnknownReporter);
+            }
+            if signers.contains(&report.public_key) {
+                return Err(OracleError::DuplicateReport);
+            }
+            if report.rate <= 0 {
+                return Err(OracleError::InvalidPrice);
+            }
+
+            // Panics on an invalid signature
+            env.crypto().ed25519_verify(
+                &report.public_key,
+                &report.hash(&env, &feed, timestamp, nonce).into(),
+                &report.signature,
+            );
+
+            signers.push_back(report.public_key.clone());
+            insert_sorted(&mut rates, report.rate);
+        }
+
+        let new_rate: i128 = median(&rates);
+        env.storage().instance().set(&REPORT_NONCE, &nonce);
+
+        if apply_price(&env, &config, new_rate, timestamp)? {
+            env.events()
+                .publish(("price_reported", nonce), (new_rate, signers.len()));
+            Ok(ReportOutcome::Accepted(new_rate))
+        } else {
+            env.events()
+                .publish(("circuit_breaker_tripped", nonce), new_rate);
+            Ok(ReportOutcome::Rejected(new_rate))
+        }
+    }
+}
+
+// Stores `new_rate` observed at `timestamp` if it passes the update interval and change limits.
+// Returns false when the change tripped the breaker instead; returning an error there would roll
+// back the trip, so the breaker is stored and the price left untouched until the admin clears it.
+fn apply_price(
+    env: &Env,
+    config: &OracleConfig,
+    new_rate: i128,
+    timestamp: u64,
+) -> Result<bool, OracleError> {
+    if get_breaker(env).tripped {
+        return Err(OracleError::CircuitBreakerTripped);
+    }
+
+    let current_price: PriceData = env
+        .storage()
+        .instance()
+        .get(&PRICE)
+        .ok_or(OracleError::NotInitialized)?;
+
+    if timestamp - current_price.timestamp < config.min_update_interval {
+        return Err(OracleError::MinimumUpdateInterval);
+    }
+
+    let price_change = ((new_rate - current_price.rate) * 100) / current_price.rate;
+    if price_change.abs() > config.max_price_change {
+        let breaker = CircuitBreaker {
+            tripped: true,
+            tripped_at: env.ledger().timestamp(),
+            rejected_rate: new_rate,
+        };
+        env.storage().instance().set(&BREAKER, &breaker);
+
+        return Ok(false);
+    }
+
+    let new_price_data = PriceData {
+        rate: new_rate,
+        timestamp,
+        valid_period: current_price.valid_period,
+    };
+
+    env.storage()
+        .instance()
+        .set(&PRICE, &new_price_data);
+
+    Ok(true)
+}
+
+fn insert_sorted(rates: &mut Vec<i128>, rate: i128) {
+    let mut index: u32 = 0;
+    while index < rates.len() && rates.get(index).unwrap() < rate {
+        index += 1;
+    }
+    rates.insert(index, rate);
+}
+
+// Median of a sorted, non-empty list; the two middle values are averaged for even lengths
+fn median(rates: &Vec<i128>) -> i128 {
+    let mid: u32 = rates.len() / 2;
+    if rates.len() % 2 == 0 {
+        (rates.get(mid - 1).unwrap() + rates.get(mid).unwrap()) / 2
+    } else {
+        rates.get(mid).unwrap()
+    }
 }
+
+fn get_breaker(env: &Env) -> CircuitBreaker {
+    env.storage()
+        .instance()
+        .get(&BREAKER)
+        .unwrap_or(CircuitBreaker {
+            tripped: false,
+            tripped_at: 0,
+            rejected_rate: 0,
+        })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,303 @@
+extern crate std;
+
+use super::{insert_sorted, median, PriceOracleContract};
+use common::pricefeed::{
+    interface::PriceOracleContractClient,
+    types::{OracleError, ReportOutcome, SignedPriceReport},
+};
+use ed25519_dalek::{Keypair, Signer};
+use rand::thread_rng;
+use common::access::{interface::AccessControlClient, types::Role};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, BytesN, Env, IntoVal, Vec,
+};
+
+const INITIAL_RATE: i128 = 1612_0000000;
+const VALID_PERIOD: u64 = 3600;
+
+struct PriceFeedTest<'a> {
+    env: Env,
+    admin: Address,
+    client: PriceOracleContractClient<'a>,
+    reporters: std::vec::Vec<Keypair>,
+}
+
+impl PriceFeedTest<'_> {
+    // A feed with three registered reporters, reports at least two of them have to agree on
+    fn setup() -> Self {
+        let env: Env = Env::default();
+        env.mock_all_auths();
+
+        let admin: Address = Address::generate(&env);
+        let contract_id: Address = env.register(PriceOracleContract, ());
+        let client = PriceOracleContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &INITIAL_RATE, &VALID_PERIOD, &1u64, &10i128);
+
+        let reporters: std::vec::Vec<Keypair> =
+            (0..3).map(|_| Keypair::generate(&mut thread_rng())).collect();
+        for reporter in reporters.iter() {
+            client.add_reporter(&admin, &public_key(&env, reporter));
+        }
+        client.set_min_reports(&admin, &2u32);
+
+        env.ledger().set_timestamp(100);
+        PriceFeedTest {
+            env,
+            admin,
+            client,
+            reporters,
+        }
+    }
+
+    fn report(&self, signer: &Keypair, rate: i128, timestamp: u64, nonce: u64) -> SignedPriceReport {
+        let mut report: SignedPriceReport = SignedPriceReport {
+            public_key: public_key(&self.env, signer),
+            rate,
+            signature: BytesN::from_array(&self.env, &[0u8; 64]),
+        };
+        let payload: BytesN<32> = report
+            .hash(&self.env, &self.client.address, timestamp, nonce)
+            .into();
+        report.signature = signer
+            .sign(payload.to_array().as_slice())
+            .to_bytes()
+            .into_val(&self.env);
+        report
+    }
+}
+
+fn public_key(env: &Env, keypair: &Keypair) -> BytesN<32> {
+    keypair.public.to_bytes().into_val(env)
+}
+
+#[test]
+fn test_reports_move_price_to_median() {
+    let test = PriceFeedTest::setup();
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1620_0000000, 100, 1),
+        test.report(&test.reporters[1], 1600_0000000, 100, 1),
+        test.report(&test.reporters[2], 1610_0000000, 100, 1),
+    ];
+
+    assert_eq!(
+        test.client.submit_reports(&100u64, &1u64, &reports),
+        ReportOutcome::Accepted(1610_0000000)
+    );
+    assert_eq!(test.client.get_price(), (1610_0000000, 100));
+    assert_eq!(test.client.get_report_nonce(), 1);
+
+    // Two reports average the middle values
+    test.env.ledger().set_timestamp(200);
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1620_0000000, 200, 2),
+        test.report(&test.reporters[1], 1630_0000000, 200, 2),
+    ];
+    assert_eq!(
+        test.client.submit_reports(&200u64, &2u64, &reports),
+        ReportOutcome::Accepted(1625_0000000)
+    );
+}
+
+#[test]
+fn test_replayed_and_unsigned_reports_rejected() {
+    let test = PriceFeedTest::setup();
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1610_0000000, 100, 1),
+        test.report(&test.reporters[1], 1610_0000000, 100, 1),
+    ];
+    test.client.submit_reports(&100u64, &1u64, &reports);
+
+    // The same bundle can't be submitted twice
+    test.env.ledger().set_timestamp(200);
+    assert_eq!(
+        test.client.try_submit_reports(&100u64, &1u64, &reports),
+        Err(Ok(OracleError::InvalidNonce))
+    );
+
+    // A signature over another nonce doesn't verify
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1610_0000000, 200, 2),
+        test.report(&test.reporters[1], 1610_0000000, 200, 3),
+    ];
+    assert!(test.client.try_submit_reports(&200u64, &2u64, &reports).is_err());
+    assert_eq!(test.client.get_report_nonce(), 1);
+}
+
+#[test]
+fn test_reports_need_known_distinct_reporters() {
+    let test = PriceFeedTest::setup();
+    let outsider: Keypair = Keypair::generate(&mut thread_rng());
+
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1610_0000000, 100, 1),
+        test.report(&outsider, 1610_0000000, 100, 1),
+    ];
+    assert_eq!(
+        test.client.try_submit_reports(&100u64, &1u64, &reports),
+        Err(Ok(OracleError::UnknownReporter))
+    );
+
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1610_0000000, 100, 1),
+        test.report(&test.reporters[0], 1610_0000000, 100, 1),
+    ];
+    assert_eq!(
+        test.client.try_submit_reports(&100u64, &1u64, &reports),
+        Err(Ok(OracleError::DuplicateReport))
+    );
+
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 1610_0000000, 100, 1),
+    ];
+    assert_eq!(
+        test.client.try_submit_reports(&100u64, &1u64, &reports),
+        Err(Ok(OracleError::InsufficientReports))
+    );
+}
+
+#[test]
+fn test_min_reports_bounded_by_reporters() {
+    let test = PriceFeedTest::setup();
+
+    assert_eq!(
+        test.client.try_set_min_reports(&test.admin, &4u32),
+        Err(Ok(OracleError::InsufficientReports))
+    );
+    test.client.set_min_reports(&test.admin, &3u32);
+
+    // Removing a reporter would leave too few to ever reach the minimum
+    let removed: BytesN<32> = public_key(&test.env, &test.reporters[2]);
+    assert_eq!(
+        test.client.try_remove_reporter(&test.admin, &removed),
+        Err(Ok(OracleError::InsufficientReports))
+    );
+    test.client.set_min_reports(&test.admin, &2u32);
+    test.client.remove_reporter(&test.admin, &removed);
+    assert_eq!(test.client.get_reporters().len(), 2);
+}
+
+#[test]
+fn test_rejected_report_trips_the_breaker() {
+    let test = PriceFeedTest::setup();
+    let reports: Vec<SignedPriceReport> = vec![
+        &test.env,
+        test.report(&test.reporters[0], 2 * INITIAL_RATE, 100, 1),
+        test.report(&test.reporters[1], 2 * INITIAL_RATE, 100, 1),
+    ];
+
+    // The relayer learns the rate was refused, the bundle is used up all the same
+    assert_eq!(
+        test.client.submit_reports(&100u64, &1u64, &reports),
+        ReportOutcome::Rejected(2 * INITIAL_RATE)
+    );
+    assert!(test.client.is_circuit_breaker_tripped());
+    assert_eq!(test.client.get_circuit_breaker().rejected_rate, 2 * INITIAL_RATE);
+    assert_eq!(test.client.get_report_nonce(), 1);
+}
+
+#[test]
+fn test_large_update_trips_the_breaker_until_reset() {
+    let test = PriceFeedTest::setup();
+
+    // Within the change limit the price just moves
+    test.client.update_price(&test.admin, &(INITIAL_RATE + INITIAL_RATE / 20));
+    assert_eq!(test.client.get_price().0, INITIAL_RATE + INITIAL_RATE / 20);
+
+    // A 100% jump is refused and halts the feed
+    test.env.ledger().set_timestamp(200);
+    test.client.update_price(&test.admin, &(2 * INITIAL_RATE));
+    assert!(test.client.is_circuit_breaker_tripped());
+    assert_eq!(test.client.get_circuit_breaker().tripped_at, 200);
+    assert_eq!(
+        test.client.try_get_price(),
+        Err(Ok(OracleError::CircuitBreakerTripped))
+    );
+    test.env.ledger().set_timestamp(300);
+    assert_eq!(
+        test.client.try_update_price(&test.admin, &INITIAL_RATE),
+        Err(Ok(OracleError::CircuitBreakerTripped))
+    );
+
+    // Reset keeps the last accepted price
+    let manager: Address = Address::generate(&test.env);
+    assert_eq!(
+        test.client.try_reset_circuit_breaker(&manager),
+        Err(Ok(OracleError::Unauthorized))
+    );
+    test.client.reset_circuit_breaker(&test.admin);
+    assert!(!test.client.is_circuit_breaker_tripped());
+    assert_eq!(test.client.get_price(), (INITIAL_RATE + INITIAL_RATE / 20, 100));
+}
+
+#[test]
+fn test_manual_trip_needs_the_pauser_role() {
+    let test = PriceFeedTest::setup();
+    let pauser: Address = Address::generate(&test.env);
+
+    assert_eq!(
+        test.client.try_trip_circuit_breaker(&pauser),
+        Err(Ok(OracleError::Unauthorized))
+    );
+    AccessControlClient::new(&test.env, &test.client.address)
+        .grant_role(&test.admin, &Role::Pauser, &pauser);
+    test.client.trip_circuit_breaker(&pauser);
+    assert!(test.client.is_circuit_breaker_tripped());
+    assert_eq!(test.client.get_circuit_breaker().rejected_rate, 0);
+    assert!(test.client.try_get_price().is_err());
+}
+
+#[test]
+fn test_force_set_price_overrides_and_clears_the_breaker() {
+    let test = PriceFeedTest::setup();
+    test.client.trip_circuit_breaker(&test.admin);
+
+    assert_eq!(
+        test.client.try_force_set_price(&test.admin, &0i128),
+        Err(Ok(OracleError::InvalidPrice))
+    );
+
+    // No interval or change limit applies to the override
+    test.client.force_set_price(&test.admin, &(3 * INITIAL_RATE));
+    let events = test.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &test.env,
+            (
+                test.client.address.clone(),
+                ("price_overridden", test.admin.clone()).into_val(&test.env),
+                (INITIAL_RATE, 3 * INITIAL_RATE).into_val(&test.env),
+            )
+        ]
+    );
+    assert!(!test.client.is_circuit_breaker_tripped());
+    assert_eq!(test.client.get_price(), (3 * INITIAL_RATE, 100));
+}
+
+#[test]
+fn test_median_of_sorted_rates() {
+    let env: Env = Env::default();
+    let mut rates: Vec<i128> = Vec::new(&env);
+
+    for rate in [30, 10, 20] {
+        insert_sorted(&mut rates, rate);
+    }
+    assert_eq!(rates, vec![&env, 10, 20, 30]);
+    assert_eq!(median(&rates), 20);
+
+    insert_sorted(&mut rates, 20);
+    assert_eq!(rates, vec![&env, 10, 20, 20, 30]);
+    assert_eq!(median(&rates), 20);
+
+    insert_sorted(&mut rates, 40);
+    insert_sorted(&mut rates, 50);
+    assert_eq!(median(&rates), 25);
+}
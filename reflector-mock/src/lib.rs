@@ -1,5 +1,8 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+const DEFAULT_DECIMALS: u32 = 14;
+const DEFAULT_RESOLUTION: u32 = 300;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -15,19 +18,102 @@ pub struct PriceData {
     pub timestamp: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Prices(Asset), // Price history for an asset, oldest first
+    Decimals,
+    Resolution,
+    Base,
+}
+
 #[contract]
 pub struct MockPriceOracleContract;
 
+// Settable stand-in for the Reflector oracle. Reads mirror the real contract: `prices` returns
+// the most recent records first and missing data is `None` rather than a panic.
 #[contractimpl]
 impl MockPriceOracleContract {
-    pub fn lastprice(_e: Env, _asset: Asset) -> Option<PriceData> {
+    pub fn set_price(e: Env, asset: Asset, price: i128, timestamp: u64) {
+        let mut history: Vec<PriceData> = get_history(&e, &asset);
+        history.push_back(PriceData { price, timestamp });
+        e.storage().instance().set(&DataKey::Prices(asset), &history);
+    }
+
+    pub fn set_decimals(e: Env, decimals: u32) {
+        e.storage().instance().set(&DataKey::Decimals, &decimals);
+    }
+
+    pub fn set_resolution(e: Env, resolution: u32) {
+        e.storage().instance().set(&DataKey::Resolution, &resolution);
+    }
+
+    pub fn set_base(e: Env, base: Asset) {
+        e.storage().instance().set(&DataKey::Base, &base);
+    }
+
+    pub fn lastprice(e: Env, asset: Asset) -> Option<PriceData> {
+        get_history(&e, &asset).last()
+    }
+
+    pub fn prices(e: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        let history: Vec<PriceData> = get_history(&e, &asset);
+        if history.is_empty() || records == 0 {
+            return None;
+        }
+
+        let mut result: Vec<PriceData> = Vec::new(&e);
+        for price in history.iter().rev().take(records as usize) {
+            result.push_back(price);
+        }
+        Some(result)
+    }
+
+    // Price of `base_asset` in `quote_asset`, with the oracle's decimals
+    pub fn x_last_price(e: Env, base_asset: Asset, quote_asset: Asset) -> Option<PriceData> {
+        let base_price: PriceData = Self::lastprice(e.clone(), base_asset)?;
+        let quote_price: PriceData = Self::lastprice(e.clone(), quote_asset)?;
+        if quote_price.price == 0 {
+            return None;
+        }
+
         Some(PriceData {
-            price: 1,
-            timestamp: 1,
+            price: (base_price.price * 10_i128.pow(Self::decimals(e))) / quote_price.price,
+            timestamp: base_price.timestamp.min(quote_price.timestamp),
         })
     }
 
-    pub fn decimals(_e: Env) -> u32 {
-        14
+    pub fn twap(e: Env, asset: Asset, records: u32) -> Option<i128> {
+        let prices: Vec<PriceData> = Self::prices(e, asset, records)?;
+        let total: i128 = prices.iter().map(|price| price.price).sum();
+        Some(total / prices.len() as i128)
     }
-}
\ No newline at end of file
+
+    pub fn decimals(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::Decimals)
+            .unwrap_or(DEFAULT_DECIMALS)
+    }
+
+    pub fn resolution(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::Resolution)
+            .unwrap_or(DEFAULT_RESOLUTION)
+    }
+
+    pub fn base(e: Env) -> Asset {
+        e.storage()
+            .instance()
+            .get(&DataKey::Base)
+            .unwrap_or(Asset::Other(Symbol::new(&e, "USD")))
+    }
+}
+
+fn get_history(e: &Env, asset: &Asset) -> Vec<PriceData> {
+    e.storage()
+        .instance()
+        .get(&DataKey::Prices(asset.clone()))
+        .unwrap_or_else(|| Vec::new(e))
+}
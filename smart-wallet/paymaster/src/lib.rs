@@ -1,5 +1,10 @@
 #![no_std]
 
+use common::impl_access_control;
+use common::access::{
+    roles::{init_admin, require_role},
+    types::Role,
+};
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env, IntoVal, Symbol, Vec
 };
@@ -20,6 +25,10 @@ enum DataKey {
 #[contract]
 pub struct Paymaster;
 
+impl_access_control!(Paymaster, |env: &Env| -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
+});
+
 #[allow(unused)]
 #[contractimpl]
 impl PayMasterInterface for Paymaster {
@@ -32,6 +41,7 @@ impl PayMasterInterface for Paymaster {
         env.storage()
             .instance()
             .set(&DataKey::LastResetTime, &env.ledger().timestamp());
+        init_admin(&env, &admin);
 
         Ok(())
     }
@@ -40,8 +50,8 @@ impl PayMasterInterface for Paymaster {
         1
     }
 
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        verify_admin(&env);
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
@@ -77,9 +87,13 @@ impl PayMasterInterface for Paymaster {
         Ok(())
     }
 
-    // Add an account to sponsored list (admin only)
-    fn add_sponsored_contracts(env: &Env, contracts: Vec<Address>) -> Result<(), Error> {
-        verify_admin(&env)?;
+    // Add contracts to the sponsored list (fee managers only)
+    fn add_sponsored_contracts(
+        env: &Env,
+        caller: Address,
+        contracts: Vec<Address>,
+    ) -> Result<(), Error> {
+        require_role(env, Role::FeeManager, &caller);
         for contract in contracts.iter() {
             env.storage()
                 .instance()
@@ -97,8 +111,13 @@ impl PayMasterInterface for Paymaster {
         Ok(())
     }
 
-    fn add_sponsored_account(env: &Env, account: Address, daily_limit: i128) -> Result<(), Error> {
-        verify_admin(&env)?;
+    fn add_sponsored_account(
+        env: &Env,
+        caller: Address,
+        account: Address,
+        daily_limit: i128,
+    ) -> Result<(), Error> {
+        require_role(env, Role::FeeManager, &caller);
         env.storage()
             .instance()
             .set(&DataKey::SponsoredAccount(account), &daily_limit);
@@ -146,8 +165,13 @@ impl PayMasterInterface for Paymaster {
         deposit >= gas_estimate
     }
 
-    fn record_gas_usage(env: &Env, account: Address, gas_used: i128) -> Result<(), Error> {
-        verify_admin(&env)?;
+    fn record_gas_usage(
+        env: &Env,
+        caller: Address,
+        account: Address,
+        gas_used: i128,
+    ) -> Result<(), Error> {
+        require_role(env, Role::FeeManager, &caller);
 
         // Reset daily limits if needed
         maybe_reset_daily_limits(&env);
@@ -268,10 +292,3 @@ fn maybe_reset_daily_limits(env: &Env) {
             .set(&DataKey::LastResetTime, &current_time);
     }
 }
-
-// Verify admin
-fn verify_admin(env: &Env) -> Result<(), Error> {
-    let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-    stored_admin.require_auth();
-    Ok(())
-}
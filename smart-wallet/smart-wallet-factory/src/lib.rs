@@ -1,5 +1,10 @@
 #![no_std]
 
+use common::impl_access_control;
+use common::access::{
+    roles::{init_admin, require_role},
+    types::Role,
+};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env, Symbol, Val, Vec
 };
@@ -21,6 +26,10 @@ pub enum Error {
 #[contract]
 pub struct SmartWalletFactory;
 
+impl_access_control!(SmartWalletFactory, |env: &Env| -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
+});
+
 const EVENT_TAG: Symbol = symbol_short!("swf_v1");
 
 #[contractimpl]
@@ -42,16 +51,17 @@ impl SmartWalletFactory {
         env.storage()
             .instance()
             .set(&DataKey::WalletWasmHash, &account_wasm_hash);
+        init_admin(env, &admin);
         Ok(())
     }
 
-    pub fn create_wallet(env: Env, salt: BytesN<32>, signer: BytesN<32>) -> Result<Address, Error> {
-        let admin = env
-            .storage()
-            .instance()
-            .get::<_, Address>(&DataKey::Admin)
-            .unwrap();
-        admin.require_auth();
+    pub fn create_wallet(
+        env: Env,
+        caller: Address,
+        salt: BytesN<32>,
+        signer: BytesN<32>,
+    ) -> Result<Address, Error> {
+        require_role(&env, Role::Admin, &caller);
 
         let wasm_hash = env
             .storage()
@@ -101,7 +101,7 @@ fn test_wallet_with_factory() {
     let salt: BytesN<32> = e.crypto().sha256(&identity.to_xdr(&e)).into();
     let wallet_address: Address = factory
         .mock_all_auths()
-        .create_wallet(&salt, &signer1_pubkey);
+        .create_wallet(&admin, &salt, &signer1_pubkey);
     let wallet: SmartWalletClient<'_> = SmartWalletClient::new(&e, &wallet_address);
     log!(&e, "Wallet address: {}", wallet_address.clone());
 
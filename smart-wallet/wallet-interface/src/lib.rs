@@ -25,12 +25,26 @@ pub trait SmartWalletInterface {
 pub trait PayMasterInterface {
     fn __constructor(env: Env, admin: Address, gas_token: Address) -> Result<(), Error>;
     fn version() -> u32;
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
     fn can_sponsor(env: &Env, account: Address, contract: Address, gas_estimate: i128) -> bool;
     fn deposit(env: &Env, from: Address, amount: i128) -> Result<(), Error>;
-    fn add_sponsored_account(env: &Env, account: Address, daily_limit: i128) -> Result<(), Error>;
-    fn add_sponsored_contracts(env: &Env, contracts: Vec<Address>) -> Result<(), Error>;
-    fn record_gas_usage(env: &Env, account: Address, gas_used: i128) -> Result<(), Error>;
+    fn add_sponsored_account(
+        env: &Env,
+        caller: Address,
+        account: Address,
+        daily_limit: i128,
+    ) -> Result<(), Error>;
+    fn add_sponsored_contracts(
+        env: &Env,
+        caller: Address,
+        contracts: Vec<Address>,
+    ) -> Result<(), Error>;
+    fn record_gas_usage(
+        env: &Env,
+        caller: Address,
+        account: Address,
+        gas_used: i128,
+    ) -> Result<(), Error>;
     fn get_deposit_balance(env: &Env, user: Address) -> i128;
     fn withdraw(env: &Env, user: Address, amount: i128) -> Result<(), Error>;
     fn get_remaining_daily_limit(env: Env, account: Address) -> u32;